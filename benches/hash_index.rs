@@ -3,6 +3,22 @@ use scc::ebr::Guard;
 use scc::HashIndex;
 use std::time::Instant;
 
+fn contains_negative(c: &mut Criterion) {
+    c.bench_function("HashIndex: contains, negative", |b| {
+        b.iter_custom(|iters| {
+            let hashindex: HashIndex<u64, u64> = HashIndex::with_capacity(iters as usize * 2);
+            for i in 0..iters {
+                assert!(hashindex.insert(i, i).is_ok());
+            }
+            let start = Instant::now();
+            for i in iters..iters * 2 {
+                assert!(!hashindex.contains(&i));
+            }
+            start.elapsed()
+        })
+    });
+}
+
 fn iter_with(c: &mut Criterion) {
     c.bench_function("HashIndex: iter_with", |b| {
         b.iter_custom(|iters| {
@@ -54,5 +70,5 @@ fn peek_with(c: &mut Criterion) {
     });
 }
 
-criterion_group!(hash_index, iter_with, peek, peek_with);
+criterion_group!(hash_index, contains_negative, iter_with, peek, peek_with);
 criterion_main!(hash_index);
@@ -1,7 +1,30 @@
 use criterion::{criterion_group, criterion_main, Criterion};
 use scc::HashMap;
+use std::hash::{BuildHasher, Hasher};
 use std::time::{Duration, Instant};
 
+/// Puts all the keys into the first bucket while giving them distinct partial hash values.
+#[derive(Clone, Default)]
+struct Overflow(u64);
+
+impl BuildHasher for Overflow {
+    type Hasher = Self;
+    fn build_hasher(&self) -> Self::Hasher {
+        Self::default()
+    }
+}
+
+impl Hasher for Overflow {
+    fn finish(&self) -> u64 {
+        self.0 & 0xff
+    }
+    fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 = self.0.wrapping_mul(31).wrapping_add(u64::from(*b));
+        }
+    }
+}
+
 fn insert_cold(c: &mut Criterion) {
     c.bench_function("HashMap: insert, cold", |b| {
         b.iter_custom(|iters| {
@@ -44,6 +67,36 @@ fn read(c: &mut Criterion) {
     });
 }
 
+fn contains_negative(c: &mut Criterion) {
+    c.bench_function("HashMap: contains, negative", |b| {
+        b.iter_custom(|iters| {
+            let hashmap: HashMap<u64, u64> = HashMap::with_capacity(iters as usize * 2);
+            for i in 0..iters {
+                assert!(hashmap.insert(i, i).is_ok());
+            }
+            let start = Instant::now();
+            for i in iters..iters * 2 {
+                assert!(!hashmap.contains(&i));
+            }
+            start.elapsed()
+        })
+    });
+}
+
+fn read_overflowed(c: &mut Criterion) {
+    c.bench_function("HashMap: read, overflowed", |b| {
+        let hashmap: HashMap<u64, u64, Overflow> = HashMap::with_hasher(Overflow::default());
+        for i in 0..256 {
+            assert!(hashmap.insert(i, i).is_ok());
+        }
+        let mut i = 0;
+        b.iter(|| {
+            assert_eq!(hashmap.read(&i, |_, v| *v == i), Some(true));
+            i = (i + 1) % 256;
+        })
+    });
+}
+
 fn insert_tail_latency(c: &mut Criterion) {
     c.bench_function("HashMap: insert, tail latency", move |b| {
         b.iter_custom(|iters| {
@@ -70,9 +123,11 @@ fn insert_tail_latency(c: &mut Criterion) {
 
 criterion_group!(
     hash_map,
+    contains_negative,
     insert_cold,
     insert_tail_latency,
     insert_warmed_up,
-    read
+    read,
+    read_overflowed
 );
 criterion_main!(hash_map);
@@ -0,0 +1,18 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use scc::Queue;
+
+fn queue_push_pop(c: &mut Criterion) {
+    let queue: Queue<usize> = Queue::default();
+    let mut i: usize = 0;
+    c.bench_function("Queue: push-pop", |b| {
+        b.iter(|| {
+            queue.push(i);
+            let p = queue.pop().map(|e| **e);
+            assert_eq!(p, Some(i));
+            i += 1;
+        })
+    });
+}
+
+criterion_group!(queue, queue_push_pop);
+criterion_main!(queue);
@@ -10,7 +10,7 @@ use std::collections::hash_map::RandomState;
 use std::fmt::{self, Debug};
 use std::hash::{BuildHasher, Hash};
 use std::mem::replace;
-use std::ops::{Deref, DerefMut, RangeInclusive};
+use std::ops::{Add, Deref, DerefMut, RangeInclusive};
 use std::pin::Pin;
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering::{Acquire, Relaxed};
@@ -59,6 +59,19 @@ use std::sync::atomic::Ordering::{Acquire, Relaxed};
 /// incrementally relocated to the new bucket array on future access to the [`HashMap`], and the old
 /// bucket array gets dropped when it becomes empty and unreachable.
 ///
+/// The load factor thresholds are not configurable: the [`HashMap`] grows when the estimated load
+/// factor reaches `7/8`, and shrinks when it drops below `1/16`. The thresholds are tied to the
+/// bucket size and the sampling-based estimation, therefore a lower load factor is achieved by
+/// [`reserve`](HashMap::reserve) or [`with_capacity`](HashMap::with_capacity) instead.
+///
+/// A bucket that keeps more than half of its capacity in overflow storage, e.g., due to a skewed
+/// hash function, estimates the load factor by itself whenever an entry is inserted into it, and
+/// the resulting resize spreads entries over more buckets. Entries sharing the upper bits of their
+/// hash values still end up in the same bucket, and a few such buckets do not raise the estimated
+/// load factor enough for the [`HashMap`] to grow.
+///
+/// Use [`HashCache`](super::HashCache) for a capacity-bounded map.
+///
 /// ### Unwind safety
 ///
 /// [`HashMap`] is impervious to out-of-memory errors and panics in user-specified code on one
@@ -85,6 +98,48 @@ where
 }
 
 /// [`OccupiedEntry`] is a view into an occupied entry in a [`HashMap`].
+///
+/// An [`OccupiedEntry`] keeps the bucket containing the entry locked until it is dropped. It can be
+/// sent to another thread if `K` and `V` are [`Send`] and [`Sync`], but it cannot outlive the
+/// [`HashMap`].
+///
+/// # Examples
+///
+/// ```
+/// use scc::HashMap;
+/// use std::thread;
+///
+/// let hashmap: HashMap<u64, u32> = HashMap::default();
+/// let mut o = hashmap.entry(1).or_insert(10);
+///
+/// thread::scope(|s| {
+///     s.spawn(move || *o.get_mut() += 1);
+/// });
+/// assert_eq!(hashmap.read(&1, |_, v| *v), Some(11));
+/// ```
+///
+/// ```compile_fail,E0597
+/// use scc::HashMap;
+/// use std::thread;
+///
+/// let hashmap: HashMap<u64, u32> = HashMap::default();
+/// let mut o = hashmap.entry(1).or_insert(10);
+///
+/// thread::spawn(move || *o.get_mut() += 1);
+/// ```
+///
+/// ```compile_fail,E0277
+/// use scc::HashMap;
+/// use std::rc::Rc;
+/// use std::thread;
+///
+/// let hashmap: HashMap<u64, Rc<u32>> = HashMap::default();
+/// let o = hashmap.entry(1).or_insert(Rc::new(10));
+///
+/// thread::scope(|s| {
+///     s.spawn(move || drop(o));
+/// });
+/// ```
 pub struct OccupiedEntry<'h, K, V, H = RandomState>
 where
     H: BuildHasher,
@@ -116,6 +171,25 @@ where
     additional: usize,
 }
 
+/// [`BucketStats`] describes how the entries of a [`HashMap`] are distributed over its buckets.
+///
+/// A skewed hash function, or adversarial keys, lead to a large number of linked buckets that
+/// slow down all the operations on the affected buckets.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct BucketStats {
+    num_buckets: usize,
+    num_entries: usize,
+    num_overflowed_buckets: usize,
+    max_linked_buckets: usize,
+}
+
+/// [`HashMapBuilder`] configures the initial capacity and the hasher of a [`HashMap`].
+#[derive(Clone, Debug, Default)]
+pub struct HashMapBuilder<H = RandomState> {
+    capacity: usize,
+    build_hasher: H,
+}
+
 impl<K, V, H> HashMap<K, V, H>
 where
     H: BuildHasher,
@@ -233,7 +307,7 @@ where
     /// assert_eq!(hashmap.capacity(), 1024);
     /// ```
     #[inline]
-    pub fn reserve(&self, additional_capacity: usize) -> Option<Reserve<K, V, H>> {
+    pub fn reserve(&self, additional_capacity: usize) -> Option<Reserve<'_, K, V, H>> {
         let additional = self.reserve_capacity(additional_capacity);
         if additional == 0 {
             None
@@ -263,7 +337,7 @@ where
     /// assert!(hashmap.read(&'y', |_, v| *v).is_none());
     /// ```
     #[inline]
-    pub fn entry(&self, key: K) -> Entry<K, V, H> {
+    pub fn entry(&self, key: K) -> Entry<'_, K, V, H> {
         let guard = Guard::new();
         let hash = self.hash(&key);
         let locked_entry = unsafe {
@@ -300,7 +374,7 @@ where
     /// let future_entry = hashmap.entry_async('b');
     /// ```
     #[inline]
-    pub async fn entry_async(&self, key: K) -> Entry<K, V, H> {
+    pub async fn entry_async(&self, key: K) -> Entry<'_, K, V, H> {
         let hash = self.hash(&key);
         loop {
             let mut async_wait = AsyncWait::default();
@@ -352,7 +426,7 @@ where
     /// assert_eq!(hashmap.read(&1, |_, v| *v), Some(2));
     /// ```
     #[inline]
-    pub fn first_entry(&self) -> Option<OccupiedEntry<K, V, H>> {
+    pub fn first_entry(&self) -> Option<OccupiedEntry<'_, K, V, H>> {
         let guard = Guard::new();
         let prolonged_guard = self.prolonged_guard_ref(&guard);
         if let Some(locked_entry) = self.lock_first_entry(prolonged_guard) {
@@ -381,7 +455,7 @@ where
     /// let future_entry = hashmap.first_entry_async();
     /// ```
     #[inline]
-    pub async fn first_entry_async(&self) -> Option<OccupiedEntry<K, V, H>> {
+    pub async fn first_entry_async(&self) -> Option<OccupiedEntry<'_, K, V, H>> {
         if let Some(locked_entry) = LockedEntry::first_entry_async(self).await {
             return Some(OccupiedEntry {
                 hashmap: self,
@@ -410,7 +484,7 @@ where
     /// assert_eq!(*entry.get(), 3);
     /// ```
     #[inline]
-    pub fn any_entry<P: FnMut(&K, &V) -> bool>(&self, pred: P) -> Option<OccupiedEntry<K, V, H>> {
+    pub fn any_entry<P: FnMut(&K, &V) -> bool>(&self, pred: P) -> Option<OccupiedEntry<'_, K, V, H>> {
         let guard = Guard::new();
         let prolonged_guard = self.prolonged_guard_ref(&guard);
         let locked_entry = self.find_entry(pred, prolonged_guard)?;
@@ -440,7 +514,7 @@ where
     pub async fn any_entry_async<P: FnMut(&K, &V) -> bool>(
         &self,
         mut pred: P,
-    ) -> Option<OccupiedEntry<K, V, H>> {
+    ) -> Option<OccupiedEntry<'_, K, V, H>> {
         if let Some(locked_entry) = LockedEntry::first_entry_async(self).await {
             let mut entry = OccupiedEntry {
                 hashmap: self,
@@ -458,6 +532,9 @@ where
 
     /// Inserts a key-value pair into the [`HashMap`].
     ///
+    /// The existing value is left untouched; use [`HashMap::upsert`] to replace it and get the old
+    /// value back.
+    ///
     /// # Errors
     ///
     /// Returns an error along with the supplied key-value pair if the key exists.
@@ -567,10 +644,78 @@ where
         }
     }
 
+    /// Adds `delta` to the value associated with the key, or inserts `V::default() + delta` if
+    /// the key does not exist.
+    ///
+    /// The value is read and updated while the bucket is exclusively locked, therefore concurrent
+    /// additions to the same key are never lost.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::HashMap;
+    ///
+    /// let hashmap: HashMap<&str, u64> = HashMap::default();
+    ///
+    /// hashmap.add("requests", 1);
+    /// hashmap.add("requests", 2);
+    /// assert_eq!(hashmap.read("requests", |_, v| *v), Some(3));
+    /// ```
+    #[inline]
+    pub fn add(&self, key: K, delta: V)
+    where
+        V: Add<Output = V> + Copy + Default,
+    {
+        match self.entry(key) {
+            Entry::Occupied(mut o) => {
+                let v = o.get_mut();
+                *v = *v + delta;
+            }
+            Entry::Vacant(v) => {
+                v.insert_entry(V::default() + delta);
+            }
+        }
+    }
+
+    /// Adds `delta` to the value associated with the key, or inserts `V::default() + delta` if
+    /// the key does not exist.
+    ///
+    /// It is an asynchronous method returning an `impl Future` for the caller to await.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::HashMap;
+    ///
+    /// let hashmap: HashMap<&str, u64> = HashMap::default();
+    /// let future_add = hashmap.add_async("requests", 1);
+    /// ```
+    #[inline]
+    pub async fn add_async(&self, key: K, delta: V)
+    where
+        V: Add<Output = V> + Copy + Default,
+    {
+        match self.entry_async(key).await {
+            Entry::Occupied(mut o) => {
+                let v = o.get_mut();
+                *v = *v + delta;
+            }
+            Entry::Vacant(v) => {
+                v.insert_entry(V::default() + delta);
+            }
+        }
+    }
+
     /// Updates an existing key-value pair in-place.
     ///
     /// Returns `None` if the key does not exist.
     ///
+    /// ## Locking behavior
+    ///
+    /// An exclusive lock on the bucket is held while `updater` is being invoked, therefore
+    /// accessing the [`HashMap`] in `updater`, or owning an [`Entry`] of the same bucket, may lead
+    /// to a deadlock; the lock is not reentrant.
+    ///
     /// # Examples
     ///
     /// ```
@@ -608,6 +753,12 @@ where
     /// Returns `None` if the key does not exist. It is an asynchronous method returning an
     /// `impl Future` for the caller to await.
     ///
+    /// ## Locking behavior
+    ///
+    /// An exclusive lock on the bucket is held while `updater` is being invoked, therefore
+    /// accessing the [`HashMap`] in `updater`, or owning an [`Entry`] of the same bucket, may lead
+    /// to a deadlock; the lock is not reentrant.
+    ///
     /// # Examples
     ///
     /// ```
@@ -645,9 +796,136 @@ where
         }
     }
 
+    /// Replaces the value of an existing key-value pair.
+    ///
+    /// Returns the old value if the key exists; otherwise, returns `None` without inserting the
+    /// key-value pair, unlike [`upsert`](Self::upsert).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::HashMap;
+    ///
+    /// let hashmap: HashMap<u64, u32> = HashMap::default();
+    ///
+    /// assert!(hashmap.replace(&1, 0).is_none());
+    /// assert!(!hashmap.contains(&1));
+    /// assert!(hashmap.insert(1, 0).is_ok());
+    /// assert_eq!(hashmap.replace(&1, 1), Some(0));
+    /// assert_eq!(hashmap.read(&1, |_, v| *v).unwrap(), 1);
+    /// ```
+    #[inline]
+    pub fn replace<Q>(&self, key: &Q, val: V) -> Option<V>
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        self.update(key, |_, v| replace(v, val))
+    }
+
+    /// Replaces the value of an existing key-value pair.
+    ///
+    /// Returns the old value if the key exists; otherwise, returns `None` without inserting the
+    /// key-value pair. It is an asynchronous method returning an `impl Future` for the caller to
+    /// await.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::HashMap;
+    ///
+    /// let hashmap: HashMap<u64, u32> = HashMap::default();
+    ///
+    /// assert!(hashmap.insert(1, 0).is_ok());
+    /// let future_replace = hashmap.replace_async(&1, 1);
+    /// ```
+    #[inline]
+    pub async fn replace_async<Q>(&self, key: &Q, val: V) -> Option<V>
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+    {
+        self.update_async(key, |_, v| replace(v, val)).await
+    }
+
+    /// Replaces the value of an existing key-value pair if it is equal to the expected value.
+    ///
+    /// Returns the old value if the value was replaced.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err(None)` if the key does not exist, or a clone of the current value if it is not
+    /// equal to the expected value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::HashMap;
+    ///
+    /// let hashmap: HashMap<u64, u32> = HashMap::default();
+    ///
+    /// assert_eq!(hashmap.replace_if(&1, &0, 1), Err(None));
+    /// assert!(hashmap.insert(1, 0).is_ok());
+    /// assert_eq!(hashmap.replace_if(&1, &1, 2), Err(Some(0)));
+    /// assert_eq!(hashmap.replace_if(&1, &0, 2), Ok(0));
+    /// assert_eq!(hashmap.read(&1, |_, v| *v).unwrap(), 2);
+    /// ```
+    #[inline]
+    pub fn replace_if<Q>(&self, key: &Q, expected: &V, val: V) -> Result<V, Option<V>>
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+        V: Clone + PartialEq,
+    {
+        self.update(key, |_, v| {
+            if v == expected {
+                Ok(replace(v, val))
+            } else {
+                Err(v.clone())
+            }
+        })
+        .map_or(Err(None), |result| result.map_err(Some))
+    }
+
+    /// Replaces the value of an existing key-value pair if it is equal to the expected value.
+    ///
+    /// Returns the old value if the value was replaced. It is an asynchronous method returning an
+    /// `impl Future` for the caller to await.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err(None)` if the key does not exist, or a clone of the current value if it is not
+    /// equal to the expected value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::HashMap;
+    ///
+    /// let hashmap: HashMap<u64, u32> = HashMap::default();
+    ///
+    /// assert!(hashmap.insert(1, 0).is_ok());
+    /// let future_replace = hashmap.replace_if_async(&1, &0, 1);
+    /// ```
+    #[inline]
+    pub async fn replace_if_async<Q>(&self, key: &Q, expected: &V, val: V) -> Result<V, Option<V>>
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+        V: Clone + PartialEq,
+    {
+        self.update_async(key, |_, v| {
+            if v == expected {
+                Ok(replace(v, val))
+            } else {
+                Err(v.clone())
+            }
+        })
+        .await
+        .map_or(Err(None), |result| result.map_err(Some))
+    }
+
     /// Removes a key-value pair if the key exists.
     ///
-    /// Returns `None` if the key does not exist.
+    /// Returns the key stored in the [`HashMap`] along with the value, which may differ from the
+    /// supplied key if `Q` is only [`Equivalent`] to `K`, e.g., when keys are normalized. Returns
+    /// `None` if the key does not exist.
     ///
     /// # Examples
     ///
@@ -769,6 +1047,10 @@ where
     /// [`OccupiedEntry`] exclusively owns the entry, preventing others from gaining access to it:
     /// use [`read`](Self::read) if read-only access is sufficient.
     ///
+    /// The [`OccupiedEntry`] can be used as a guard when the value is modified in multiple steps:
+    /// it dereferences to `V`, holds the lock on the bucket until it is dropped, and borrows the
+    /// [`HashMap`], therefore it cannot outlive the [`HashMap`].
+    ///
     /// Returns `None` if the key does not exist.
     ///
     /// # Examples
@@ -784,9 +1066,26 @@ where
     ///
     /// *hashmap.get(&1).unwrap() = 11;
     /// assert_eq!(*hashmap.get(&1).unwrap(), 11);
+    ///
+    /// let mut entry = hashmap.get(&1).unwrap();
+    /// *entry += 1;
+    /// *entry *= 2;
+    /// drop(entry);
+    /// assert_eq!(hashmap.read(&1, |_, v| *v), Some(24));
+    /// ```
+    ///
+    /// ```compile_fail,E0505
+    /// use scc::HashMap;
+    ///
+    /// let hashmap: HashMap<u64, u32> = HashMap::default();
+    /// assert!(hashmap.insert(1, 10).is_ok());
+    ///
+    /// let mut entry = hashmap.get(&1).unwrap();
+    /// drop(hashmap);
+    /// *entry += 1;
     /// ```
     #[inline]
-    pub fn get<Q>(&self, key: &Q) -> Option<OccupiedEntry<K, V, H>>
+    pub fn get<Q>(&self, key: &Q) -> Option<OccupiedEntry<'_, K, V, H>>
     where
         Q: Equivalent<K> + Hash + ?Sized,
     {
@@ -824,7 +1123,7 @@ where
     /// let future_get = hashmap.get_async(&11);
     /// ```
     #[inline]
-    pub async fn get_async<Q>(&self, key: &Q) -> Option<OccupiedEntry<K, V, H>>
+    pub async fn get_async<Q>(&self, key: &Q) -> Option<OccupiedEntry<'_, K, V, H>>
     where
         Q: Equivalent<K> + Hash + ?Sized,
     {
@@ -850,10 +1149,112 @@ where
         }
     }
 
+    /// Returns a clone of the value associated with the key.
+    ///
+    /// A shared lock on the bucket is held only while the value is being cloned. Returns `None`
+    /// if the key does not exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::HashMap;
+    ///
+    /// let hashmap: HashMap<u64, u32> = HashMap::default();
+    ///
+    /// assert!(hashmap.get_cloned(&1).is_none());
+    /// assert!(hashmap.insert(1, 10).is_ok());
+    /// assert_eq!(hashmap.get_cloned(&1), Some(10));
+    /// ```
+    #[inline]
+    pub fn get_cloned<Q>(&self, key: &Q) -> Option<V>
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+        V: Clone,
+    {
+        self.read(key, |_, v| v.clone())
+    }
+
+    /// Returns a clone of the value associated with the key.
+    ///
+    /// Returns `None` if the key does not exist. It is an asynchronous method returning an
+    /// `impl Future` for the caller to await.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::HashMap;
+    ///
+    /// let hashmap: HashMap<u64, u32> = HashMap::default();
+    /// let future_insert = hashmap.insert_async(11, 17);
+    /// let future_get_cloned = hashmap.get_cloned_async(&11);
+    /// ```
+    #[inline]
+    pub async fn get_cloned_async<Q>(&self, key: &Q) -> Option<V>
+    where
+        Q: Equivalent<K> + Hash + ?Sized,
+        V: Clone,
+    {
+        self.read_async(key, |_, v| v.clone()).await
+    }
+
+    /// Gets the entry associated with the key, inserting `V::default()` if the key does not exist.
+    ///
+    /// Looking up and inserting the default value happen under a single exclusive lock on the
+    /// bucket, therefore the default value is inserted only once even if multiple threads call
+    /// this method with the same key concurrently.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::HashMap;
+    ///
+    /// let hashmap: HashMap<u64, Vec<u32>> = HashMap::default();
+    ///
+    /// hashmap.get_or_insert_default(1).get_mut().push(3);
+    /// hashmap.get_or_insert_default(1).get_mut().push(7);
+    /// assert_eq!(hashmap.get_cloned(&1), Some(vec![3, 7]));
+    /// ```
+    #[inline]
+    pub fn get_or_insert_default(&self, key: K) -> OccupiedEntry<'_, K, V, H>
+    where
+        V: Default,
+    {
+        self.entry(key).or_default()
+    }
+
+    /// Gets the entry associated with the key, inserting `V::default()` if the key does not exist.
+    ///
+    /// It is an asynchronous method returning an `impl Future` for the caller to await.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::HashMap;
+    ///
+    /// let hashmap: HashMap<u64, Vec<u32>> = HashMap::default();
+    /// let future_get_or_insert_default = hashmap.get_or_insert_default_async(1);
+    /// ```
+    #[inline]
+    pub async fn get_or_insert_default_async(&self, key: K) -> OccupiedEntry<'_, K, V, H>
+    where
+        V: Default,
+    {
+        self.entry_async(key).await.or_default()
+    }
+
     /// Reads a key-value pair.
     ///
     /// Returns `None` if the key does not exist.
     ///
+    /// The key can be of any type `Q` that is [`Equivalent`] to `K`, e.g., `&str` for `String`
+    /// keys, as long as `Q` and `K` generate the same hash value, therefore no `K` needs to be
+    /// constructed for lookups.
+    ///
+    /// ## Locking behavior
+    ///
+    /// A shared lock on the bucket is held while `reader` is being invoked, therefore modifying the
+    /// [`HashMap`] in `reader`, or owning an [`Entry`] of the same bucket, may lead to a deadlock.
+    ///
     /// # Examples
     ///
     /// ```
@@ -864,6 +1265,11 @@ where
     /// assert!(hashmap.read(&1, |_, v| *v).is_none());
     /// assert!(hashmap.insert(1, 10).is_ok());
     /// assert_eq!(hashmap.read(&1, |_, v| *v).unwrap(), 10);
+    ///
+    /// let hashmap: HashMap<String, u32> = HashMap::default();
+    ///
+    /// assert!(hashmap.insert("one".to_string(), 1).is_ok());
+    /// assert_eq!(hashmap.read("one", |_, v| *v).unwrap(), 1);
     /// ```
     #[inline]
     pub fn read<Q, R, F: FnOnce(&K, &V) -> R>(&self, key: &Q, reader: F) -> Option<R>
@@ -880,6 +1286,11 @@ where
     /// Returns `None` if the key does not exist. It is an asynchronous method returning an
     /// `impl Future` for the caller to await.
     ///
+    /// ## Locking behavior
+    ///
+    /// A shared lock on the bucket is held while `reader` is being invoked, therefore modifying the
+    /// [`HashMap`] in `reader`, or owning an [`Entry`] of the same bucket, may lead to a deadlock.
+    ///
     /// # Examples
     ///
     /// ```
@@ -913,6 +1324,10 @@ where
 
     /// Returns `true` if the [`HashMap`] contains a value for the specified key.
     ///
+    /// A shared lock on the bucket is acquired even if the key turns out to be absent, since slots
+    /// of a [`HashMap`] bucket are reused and overwritten without synchronizing with lock-free
+    /// readers. Use [`HashIndex`](crate::HashIndex) if lookups have to be lock-free.
+    ///
     /// # Examples
     ///
     /// ```
@@ -957,7 +1372,8 @@ where
     ///
     /// Key-value pairs that have existed since the invocation of the method are guaranteed to be
     /// visited if they are not removed, however the same key-value pair can be visited more than
-    /// once if the [`HashMap`] gets resized by another thread.
+    /// once if the [`HashMap`] gets resized by another thread. Key-value pairs inserted or removed
+    /// by other threads during the scan may or may not be visited.
     ///
     /// # Examples
     ///
@@ -972,6 +1388,12 @@ where
     /// let mut sum = 0;
     /// hashmap.scan(|k, v| { sum += *k + *v; });
     /// assert_eq!(sum, 4);
+    ///
+    /// // Take a snapshot of the entries that can be used without holding any locks.
+    /// let mut snapshot = Vec::new();
+    /// hashmap.scan(|k, v| snapshot.push((*k, *v)));
+    /// snapshot.sort_unstable();
+    /// assert_eq!(snapshot, vec![(1, 0), (2, 1)]);
     /// ```
     #[inline]
     pub fn scan<F: FnMut(&K, &V)>(&self, mut scanner: F) {
@@ -1006,42 +1428,104 @@ where
         .await;
     }
 
-    /// Searches for any entry that satisfies the given predicate.
-    ///
-    /// Key-value pairs that have existed since the invocation of the method are guaranteed to be
-    /// visited if they are not removed, however the same key-value pair can be visited more than
-    /// once if the [`HashMap`] gets resized by another thread.
+    /// Returns a snapshot of all the entries sorted by key.
     ///
-    /// Returns `true` as soon as an entry satisfying the predicate is found.
+    /// The entries are cloned while each bucket is shared-locked, and then sorted without holding
+    /// any locks, so that the order of the returned entries is reproducible across runs regardless
+    /// of the hasher. Entries visited more than once because of a concurrent resize are reported
+    /// only once; entries inserted or removed by other threads during the scan may or may not be
+    /// included.
     ///
     /// # Examples
     ///
     /// ```
     /// use scc::HashMap;
     ///
-    /// let hashmap: HashMap<u64, u32> = HashMap::default();
+    /// let hashmap: HashMap<usize, usize> = HashMap::default();
     ///
-    /// assert!(hashmap.insert(1, 0).is_ok());
-    /// assert!(hashmap.insert(2, 1).is_ok());
-    /// assert!(hashmap.insert(3, 2).is_ok());
+    /// assert!(hashmap.insert(3, 30).is_ok());
+    /// assert!(hashmap.insert(1, 10).is_ok());
+    /// assert!(hashmap.insert(2, 20).is_ok());
     ///
-    /// assert!(hashmap.any(|k, v| *k == 1 && *v == 0));
-    /// assert!(!hashmap.any(|k, v| *k == 2 && *v == 0));
+    /// assert_eq!(hashmap.scan_ordered(), vec![(1, 10), (2, 20), (3, 30)]);
     /// ```
     #[inline]
-    pub fn any<P: FnMut(&K, &V) -> bool>(&self, pred: P) -> bool {
-        self.contains_entry(pred)
+    pub fn scan_ordered(&self) -> Vec<(K, V)>
+    where
+        K: Clone + Ord,
+        V: Clone,
+    {
+        let mut entries = Vec::with_capacity(self.len());
+        self.scan(|k, v| entries.push((k.clone(), v.clone())));
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        entries.dedup_by(|a, b| a.0 == b.0);
+        entries
     }
 
-    /// Searches for any entry that satisfies the given predicate.
-    ///
-    /// Key-value pairs that have existed since the invocation of the method are guaranteed to be
-    /// visited if they are not removed, however the same key-value pair can be visited more than
-    /// once if the [`HashMap`] gets resized by another task.
+    /// Returns a snapshot of all the entries sorted by key.
     ///
     /// It is an asynchronous method returning an `impl Future` for the caller to await.
     ///
-    /// Returns `true` as soon as an entry satisfying the predicate is found.
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::HashMap;
+    ///
+    /// let hashmap: HashMap<usize, usize> = HashMap::default();
+    ///
+    /// let future_insert = hashmap.insert_async(1, 10);
+    /// let future_scan_ordered = hashmap.scan_ordered_async();
+    /// ```
+    #[inline]
+    pub async fn scan_ordered_async(&self) -> Vec<(K, V)>
+    where
+        K: Clone + Ord,
+        V: Clone,
+    {
+        let mut entries = Vec::with_capacity(self.len());
+        self.scan_async(|k, v| entries.push((k.clone(), v.clone())))
+            .await;
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        entries.dedup_by(|a, b| a.0 == b.0);
+        entries
+    }
+
+    /// Searches for any entry that satisfies the given predicate.
+    ///
+    /// Key-value pairs that have existed since the invocation of the method are guaranteed to be
+    /// visited if they are not removed, however the same key-value pair can be visited more than
+    /// once if the [`HashMap`] gets resized by another thread.
+    ///
+    /// Returns `true` as soon as an entry satisfying the predicate is found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::HashMap;
+    ///
+    /// let hashmap: HashMap<u64, u32> = HashMap::default();
+    ///
+    /// assert!(hashmap.insert(1, 0).is_ok());
+    /// assert!(hashmap.insert(2, 1).is_ok());
+    /// assert!(hashmap.insert(3, 2).is_ok());
+    ///
+    /// assert!(hashmap.any(|k, v| *k == 1 && *v == 0));
+    /// assert!(!hashmap.any(|k, v| *k == 2 && *v == 0));
+    /// ```
+    #[inline]
+    pub fn any<P: FnMut(&K, &V) -> bool>(&self, pred: P) -> bool {
+        self.contains_entry(pred)
+    }
+
+    /// Searches for any entry that satisfies the given predicate.
+    ///
+    /// Key-value pairs that have existed since the invocation of the method are guaranteed to be
+    /// visited if they are not removed, however the same key-value pair can be visited more than
+    /// once if the [`HashMap`] gets resized by another task.
+    ///
+    /// It is an asynchronous method returning an `impl Future` for the caller to await.
+    ///
+    /// Returns `true` as soon as an entry satisfying the predicate is found.
     ///
     /// # Examples
     ///
@@ -1099,6 +1583,178 @@ where
         false
     }
 
+    /// Folds all the entries into an accumulator.
+    ///
+    /// Key-value pairs that have existed since the invocation of the method are guaranteed to be
+    /// visited if they are not removed, however the same key-value pair can be visited more than
+    /// once if the [`HashMap`] gets resized by another thread. Key-value pairs inserted or removed
+    /// by other threads during the fold may or may not be visited, therefore the result is exact
+    /// only if the [`HashMap`] is not modified concurrently.
+    ///
+    /// Each bucket is shared-locked only while its entries are being folded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::HashMap;
+    ///
+    /// let hashmap: HashMap<usize, usize> = HashMap::default();
+    ///
+    /// assert!(hashmap.insert(1, 10).is_ok());
+    /// assert!(hashmap.insert(2, 20).is_ok());
+    ///
+    /// assert_eq!(hashmap.fold(0, |acc, _, v| acc + *v), 30);
+    /// assert_eq!(hashmap.fold(0, |acc, k, _| acc.max(*k)), 2);
+    /// ```
+    #[inline]
+    pub fn fold<A, F: FnMut(A, &K, &V) -> A>(&self, init: A, mut f: F) -> A {
+        let mut acc = init;
+        let guard = Guard::new();
+        let mut current_array_ptr = self.array.load(Acquire, &guard);
+        while let Some(current_array) = current_array_ptr.as_ref() {
+            self.clear_old_array(current_array, &guard);
+            for index in 0..current_array.num_buckets() {
+                let bucket = current_array.bucket(index);
+                if let Some(reader) = Reader::lock(bucket, &guard) {
+                    let data_block = current_array.data_block(index);
+                    let mut entry_ptr = EntryPtr::new(&guard);
+                    while entry_ptr.move_to_next(*reader, &guard) {
+                        let (k, v) = entry_ptr.get(data_block);
+                        acc = f(acc, k, v);
+                    }
+                }
+            }
+
+            let new_current_array_ptr = self.array.load(Acquire, &guard);
+            if current_array_ptr.without_tag() == new_current_array_ptr.without_tag() {
+                break;
+            }
+            current_array_ptr = new_current_array_ptr;
+        }
+        acc
+    }
+
+    /// Folds all the entries into an accumulator.
+    ///
+    /// Key-value pairs that have existed since the invocation of the method are guaranteed to be
+    /// visited if they are not removed, however the same key-value pair can be visited more than
+    /// once if the [`HashMap`] gets resized by another task.
+    ///
+    /// It is an asynchronous method returning an `impl Future` for the caller to await.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::HashMap;
+    ///
+    /// let hashmap: HashMap<usize, usize> = HashMap::default();
+    ///
+    /// let future_insert = hashmap.insert_async(1, 10);
+    /// let future_fold = hashmap.fold_async(0, |acc, _, v| acc + *v);
+    /// ```
+    #[inline]
+    pub async fn fold_async<A, F: FnMut(A, &K, &V) -> A>(&self, init: A, mut f: F) -> A {
+        let mut acc = init;
+        let mut current_array_holder = self.array.get_shared(Acquire, &Guard::new());
+        while let Some(current_array) = current_array_holder.take() {
+            self.cleanse_old_array_async(&current_array).await;
+            for index in 0..current_array.num_buckets() {
+                loop {
+                    let mut async_wait = AsyncWait::default();
+                    let mut async_wait_pinned = Pin::new(&mut async_wait);
+                    {
+                        let guard = Guard::new();
+                        let bucket = current_array.bucket(index);
+                        if let Ok(reader) =
+                            Reader::try_lock_or_wait(bucket, &mut async_wait_pinned, &guard)
+                        {
+                            if let Some(reader) = reader {
+                                let data_block = current_array.data_block(index);
+                                let mut entry_ptr = EntryPtr::new(&guard);
+                                while entry_ptr.move_to_next(*reader, &guard) {
+                                    let (k, v) = entry_ptr.get(data_block);
+                                    acc = f(acc, k, v);
+                                }
+                            }
+                            break;
+                        };
+                    }
+                    async_wait_pinned.await;
+                }
+            }
+
+            if let Some(new_current_array) = self.array.get_shared(Acquire, &Guard::new()) {
+                if new_current_array.as_ptr() == current_array.as_ptr() {
+                    break;
+                }
+                current_array_holder.replace(new_current_array);
+                continue;
+            }
+            break;
+        }
+        acc
+    }
+
+    /// Visits all the entries, allowing the closure to modify the values.
+    ///
+    /// Each bucket is exclusively locked while its entries are being visited, and only one bucket
+    /// is locked at a time.
+    ///
+    /// Entries that have existed since the invocation of the method are guaranteed to be visited
+    /// if they are not removed, however the same entry can be visited more than once if the
+    /// [`HashMap`] gets resized by another thread. Entries inserted or removed by other threads
+    /// during the method call may or may not be visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::HashMap;
+    ///
+    /// let hashmap: HashMap<u64, u32> = HashMap::default();
+    ///
+    /// assert!(hashmap.insert(1, 1).is_ok());
+    /// assert!(hashmap.insert(2, 2).is_ok());
+    ///
+    /// hashmap.for_each_mut(|_, v| *v *= 10);
+    ///
+    /// assert_eq!(hashmap.read(&1, |_, v| *v), Some(10));
+    /// assert_eq!(hashmap.read(&2, |_, v| *v), Some(20));
+    /// ```
+    #[inline]
+    pub fn for_each_mut<F: FnMut(&K, &mut V)>(&self, mut f: F) {
+        self.retain(|k, v| {
+            f(k, v);
+            true
+        });
+    }
+
+    /// Visits all the entries, allowing the closure to modify the values.
+    ///
+    /// Entries that have existed since the invocation of the method are guaranteed to be visited
+    /// if they are not removed, however the same entry can be visited more than once if the
+    /// [`HashMap`] gets resized by another task.
+    ///
+    /// It is an asynchronous method returning an `impl Future` for the caller to await.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::HashMap;
+    ///
+    /// let hashmap: HashMap<u64, u32> = HashMap::default();
+    ///
+    /// let future_insert = hashmap.insert_async(1, 1);
+    /// let future_for_each_mut = hashmap.for_each_mut_async(|_, v| *v *= 10);
+    /// ```
+    #[inline]
+    pub async fn for_each_mut_async<F: FnMut(&K, &mut V)>(&self, mut f: F) {
+        self.retain_async(|k, v| {
+            f(k, v);
+            true
+        })
+        .await;
+    }
+
     /// Retains the entries specified by the predicate.
     ///
     /// This method allows the predicate closure to modify the value field.
@@ -1198,6 +1854,67 @@ where
         }
     }
 
+    /// Retains the entries specified by the predicate, and returns the number of removed entries.
+    ///
+    /// This method allows the predicate closure to modify the value field.
+    ///
+    /// The returned number is exact for the entries visited by the method: an entry is counted
+    /// only if the predicate returned `false` for it, and therefore it was removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::HashMap;
+    ///
+    /// let hashmap: HashMap<u64, u32> = HashMap::default();
+    ///
+    /// assert!(hashmap.insert(1, 0).is_ok());
+    /// assert!(hashmap.insert(2, 1).is_ok());
+    /// assert!(hashmap.insert(3, 2).is_ok());
+    ///
+    /// assert_eq!(hashmap.retain_count(|k, _| *k == 1), 2);
+    /// assert_eq!(hashmap.retain_count(|k, _| *k == 1), 0);
+    /// assert_eq!(hashmap.len(), 1);
+    /// ```
+    #[inline]
+    pub fn retain_count<F: FnMut(&K, &mut V) -> bool>(&self, mut pred: F) -> usize {
+        let mut num_removed = 0;
+        self.retain(|k, v| {
+            let retain = pred(k, v);
+            num_removed += usize::from(!retain);
+            retain
+        });
+        num_removed
+    }
+
+    /// Retains the entries specified by the predicate, and returns the number of removed entries.
+    ///
+    /// This method allows the predicate closure to modify the value field.
+    ///
+    /// It is an asynchronous method returning an `impl Future` for the caller to await.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::HashMap;
+    ///
+    /// let hashmap: HashMap<u64, u32> = HashMap::default();
+    ///
+    /// let future_insert = hashmap.insert_async(1, 0);
+    /// let future_retain_count = hashmap.retain_count_async(|k, _| *k == 1);
+    /// ```
+    #[inline]
+    pub async fn retain_count_async<F: FnMut(&K, &mut V) -> bool>(&self, mut pred: F) -> usize {
+        let mut num_removed = 0;
+        self.retain_async(|k, v| {
+            let retain = pred(k, v);
+            num_removed += usize::from(!retain);
+            retain
+        })
+        .await;
+        num_removed
+    }
+
     /// Prunes the entries specified by the predicate.
     ///
     /// If the value is consumed by the predicate, in other words, if the predicate returns `None`,
@@ -1337,11 +2054,181 @@ where
         self.retain_async(|_, _| false).await;
     }
 
+    /// Removes all the key-value pairs, and returns them.
+    ///
+    /// Key-value pairs inserted by other threads during the method call may or may not be removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::HashMap;
+    ///
+    /// let hashmap: HashMap<u64, u32> = HashMap::default();
+    ///
+    /// assert!(hashmap.insert(1, 0).is_ok());
+    /// assert!(hashmap.insert(2, 1).is_ok());
+    ///
+    /// let mut drained = hashmap.drain();
+    /// drained.sort_unstable();
+    /// assert_eq!(drained, vec![(1, 0), (2, 1)]);
+    /// assert!(hashmap.is_empty());
+    /// ```
+    #[inline]
+    pub fn drain(&self) -> Vec<(K, V)> {
+        self.extract_if(|_, _| true)
+    }
+
+    /// Removes all the key-value pairs, and returns them.
+    ///
+    /// Key-value pairs inserted by other tasks during the method call may or may not be removed.
+    /// It is an asynchronous method returning an `impl Future` for the caller to await.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::HashMap;
+    ///
+    /// let hashmap: HashMap<u64, u32> = HashMap::default();
+    ///
+    /// let future_insert = hashmap.insert_async(1, 0);
+    /// let future_drain = hashmap.drain_async();
+    /// ```
+    #[inline]
+    pub async fn drain_async(&self) -> Vec<(K, V)> {
+        self.extract_if_async(|_, _| true).await
+    }
+
+    /// Removes the key-value pairs specified by the predicate, and returns them.
+    ///
+    /// The predicate and the removal of matching entries are performed while the bucket is
+    /// exclusively locked, and the removed key-value pairs are moved out of the [`HashMap`]
+    /// without being cloned. Entries that have existed since the invocation of the method are
+    /// guaranteed to be visited if they are not removed, however the same entry can be visited more
+    /// than once if the [`HashMap`] gets resized by another thread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::HashMap;
+    ///
+    /// let hashmap: HashMap<u64, u32> = HashMap::default();
+    ///
+    /// assert!(hashmap.insert(1, 0).is_ok());
+    /// assert!(hashmap.insert(2, 1).is_ok());
+    /// assert!(hashmap.insert(3, 2).is_ok());
+    ///
+    /// let mut extracted = hashmap.extract_if(|k, _| *k != 2);
+    /// extracted.sort_unstable();
+    /// assert_eq!(extracted, vec![(1, 0), (3, 2)]);
+    /// assert_eq!(hashmap.len(), 1);
+    /// ```
+    #[inline]
+    pub fn extract_if<F: FnMut(&K, &V) -> bool>(&self, mut pred: F) -> Vec<(K, V)> {
+        let guard = Guard::new();
+        let mut extracted = Vec::new();
+        let mut current_array_ptr = self.array.load(Acquire, &guard);
+        while let Some(current_array) = current_array_ptr.as_ref() {
+            self.clear_old_array(current_array, &guard);
+            for index in 0..current_array.num_buckets() {
+                let bucket = current_array.bucket_mut(index);
+                if let Some(mut locker) = Locker::lock(bucket, &guard) {
+                    let data_block_mut = current_array.data_block_mut(index);
+                    let mut entry_ptr = EntryPtr::new(&guard);
+                    while entry_ptr.move_to_next(&locker, &guard) {
+                        let (k, v) = entry_ptr.get(data_block_mut);
+                        if pred(k, v) {
+                            extracted.push(locker.remove(data_block_mut, &mut entry_ptr, &guard));
+                        }
+                    }
+                }
+            }
+
+            let new_current_array_ptr = self.array.load(Acquire, &guard);
+            if current_array_ptr.without_tag() == new_current_array_ptr.without_tag() {
+                break;
+            }
+            current_array_ptr = new_current_array_ptr;
+        }
+
+        if !extracted.is_empty() {
+            self.try_resize(0, &guard);
+        }
+        extracted
+    }
+
+    /// Removes the key-value pairs specified by the predicate, and returns them.
+    ///
+    /// It is an asynchronous method returning an `impl Future` for the caller to await.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::HashMap;
+    ///
+    /// let hashmap: HashMap<u64, u32> = HashMap::default();
+    ///
+    /// let future_insert = hashmap.insert_async(1, 0);
+    /// let future_extract_if = hashmap.extract_if_async(|k, _| *k == 1);
+    /// ```
+    #[inline]
+    pub async fn extract_if_async<F: FnMut(&K, &V) -> bool>(&self, mut pred: F) -> Vec<(K, V)> {
+        let mut extracted = Vec::new();
+        let mut current_array_holder = self.array.get_shared(Acquire, &Guard::new());
+        while let Some(current_array) = current_array_holder.take() {
+            self.cleanse_old_array_async(&current_array).await;
+            for index in 0..current_array.num_buckets() {
+                loop {
+                    let mut async_wait = AsyncWait::default();
+                    let mut async_wait_pinned = Pin::new(&mut async_wait);
+                    {
+                        let guard = Guard::new();
+                        let bucket = current_array.bucket_mut(index);
+                        if let Ok(locker) =
+                            Locker::try_lock_or_wait(bucket, &mut async_wait_pinned, &guard)
+                        {
+                            if let Some(mut locker) = locker {
+                                let data_block_mut = current_array.data_block_mut(index);
+                                let mut entry_ptr = EntryPtr::new(&guard);
+                                while entry_ptr.move_to_next(&locker, &guard) {
+                                    let (k, v) = entry_ptr.get(data_block_mut);
+                                    if pred(k, v) {
+                                        extracted.push(locker.remove(
+                                            data_block_mut,
+                                            &mut entry_ptr,
+                                            &guard,
+                                        ));
+                                    }
+                                }
+                            }
+                            break;
+                        };
+                    }
+                    async_wait_pinned.await;
+                }
+            }
+
+            if let Some(new_current_array) = self.array.get_shared(Acquire, &Guard::new()) {
+                if new_current_array.as_ptr() == current_array.as_ptr() {
+                    break;
+                }
+                current_array_holder.replace(new_current_array);
+                continue;
+            }
+            break;
+        }
+
+        if !extracted.is_empty() {
+            self.try_resize(0, &Guard::new());
+        }
+        extracted
+    }
+
     /// Returns the number of entries in the [`HashMap`].
     ///
     /// It reads the entire metadata area of the bucket array to calculate the number of valid
     /// entries, making its time complexity `O(N)`. Furthermore, it may overcount entries if an old
-    /// bucket array has yet to be dropped.
+    /// bucket array has yet to be dropped. Buckets are not locked while being read, therefore the
+    /// result is only exact if no other threads are modifying the [`HashMap`].
     ///
     /// # Examples
     ///
@@ -1378,6 +2265,10 @@ where
 
     /// Returns the capacity of the [`HashMap`].
     ///
+    /// The capacity is the number of entry slots in the current bucket array, which is the new
+    /// array if the [`HashMap`] is being resized. See [Resize](HashMap#resize) for when the
+    /// [`HashMap`] grows and shrinks.
+    ///
     /// # Examples
     ///
     /// ```
@@ -1416,6 +2307,27 @@ where
         self.minimum_capacity.load(Relaxed)..=self.maximum_capacity()
     }
 
+    /// Returns a reference to the [`BuildHasher`] of the [`HashMap`].
+    ///
+    /// Hash values computed with the returned [`BuildHasher`] are identical to those used by the
+    /// [`HashMap`] for the same key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::HashMap;
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let build_hasher = RandomState::new();
+    /// let hashmap: HashMap<u64, u32, RandomState> = HashMap::with_hasher(build_hasher.clone());
+    ///
+    /// let hasher: &RandomState = hashmap.hasher();
+    /// ```
+    #[inline]
+    pub fn hasher(&self) -> &H {
+        &self.build_hasher
+    }
+
     /// Returns the index of the bucket that may contain the key.
     ///
     /// The method returns the index of the bucket associated with the key. The number of buckets
@@ -1439,6 +2351,65 @@ where
         self.calculate_bucket_index(key)
     }
 
+    /// Returns statistics on how the entries are distributed over the buckets.
+    ///
+    /// Buckets are read-locked one at a time, therefore entries inserted or removed by other
+    /// threads at the same time may or may not be reflected in the statistics. The method does not
+    /// relocate entries while the [`HashMap`] is being resized: entries that are yet to be
+    /// relocated are counted in the number of entries, but not in the other statistics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::HashMap;
+    ///
+    /// let hashmap: HashMap<u64, u32> = HashMap::with_capacity(1024);
+    /// assert!(hashmap.insert(1, 0).is_ok());
+    ///
+    /// let bucket_stats = hashmap.bucket_stats();
+    /// assert_eq!(bucket_stats.num_buckets(), hashmap.capacity() / 32);
+    /// assert_eq!(bucket_stats.num_entries(), 1);
+    /// assert_eq!(bucket_stats.max_linked_buckets(), 0);
+    /// ```
+    #[inline]
+    pub fn bucket_stats(&self) -> BucketStats {
+        let guard = Guard::new();
+        let mut bucket_stats = BucketStats::default();
+        let mut current_array_ptr = self.array.load(Acquire, &guard);
+        while let Some(current_array) = current_array_ptr.as_ref() {
+            bucket_stats = BucketStats {
+                num_buckets: current_array.num_buckets(),
+                ..BucketStats::default()
+            };
+            if let Some(old_array) = current_array.old_array(&guard).as_ref() {
+                // Relocated buckets are killed, and `Reader::lock` returns `None` for them.
+                for index in 0..old_array.num_buckets() {
+                    if let Some(reader) = Reader::lock(old_array.bucket(index), &guard) {
+                        bucket_stats.num_entries += reader.num_entries();
+                    }
+                }
+            }
+            for index in 0..current_array.num_buckets() {
+                if let Some(reader) = Reader::lock(current_array.bucket(index), &guard) {
+                    let num_linked_buckets = reader.num_linked_buckets(&guard);
+                    bucket_stats.num_entries += reader.num_entries();
+                    if num_linked_buckets != 0 {
+                        bucket_stats.num_overflowed_buckets += 1;
+                    }
+                    bucket_stats.max_linked_buckets =
+                        bucket_stats.max_linked_buckets.max(num_linked_buckets);
+                }
+            }
+
+            let new_current_array_ptr = self.array.load(Acquire, &guard);
+            if current_array_ptr.without_tag() == new_current_array_ptr.without_tag() {
+                break;
+            }
+            current_array_ptr = new_current_array_ptr;
+        }
+        bucket_stats
+    }
+
     /// Clears the old array asynchronously.
     async fn cleanse_old_array_async(&self, current_array: &BucketArray<K, V, (), SEQUENTIAL>) {
         while current_array.has_old_array() {
@@ -1457,6 +2428,83 @@ where
     }
 }
 
+#[cfg(feature = "rayon")]
+impl<K, V, H> HashMap<K, V, H>
+where
+    K: Eq + Hash + Sync,
+    V: Sync,
+    H: BuildHasher,
+{
+    /// Visits all the entries in parallel using the [`rayon`] thread pool.
+    ///
+    /// Each bucket is assigned to exactly one `rayon` task, and the task holds a shared lock on
+    /// the bucket while visiting its entries, therefore the closure may be invoked by multiple
+    /// threads at the same time, but never for entries in the same bucket.
+    ///
+    /// Key-value pairs that have existed since the invocation of the method are guaranteed to be
+    /// visited if they are not removed, however the same key-value pair can be visited more than
+    /// once if the [`HashMap`] gets resized by another thread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::HashMap;
+    /// use std::sync::atomic::AtomicUsize;
+    /// use std::sync::atomic::Ordering::Relaxed;
+    ///
+    /// let hashmap: HashMap<usize, usize> = (0..1024).map(|k| (k, k)).collect();
+    ///
+    /// let sum = AtomicUsize::new(0);
+    /// hashmap.par_for_each(|_, v| {
+    ///     sum.fetch_add(*v, Relaxed);
+    /// });
+    /// assert_eq!(sum.load(Relaxed), 1023 * 1024 / 2);
+    /// ```
+    #[inline]
+    pub fn par_for_each<F: Fn(&K, &V) + Send + Sync>(&self, f: F) {
+        use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+        /// Read-only view of a [`BucketArray`] that can be shared among `rayon` tasks.
+        struct SharedArray<'a, K, V>(&'a BucketArray<K, V, (), SEQUENTIAL>);
+
+        impl<'a, K, V> SharedArray<'a, K, V> {
+            fn get(&self) -> &'a BucketArray<K, V, (), SEQUENTIAL> {
+                self.0
+            }
+        }
+
+        // Safety: `rayon` tasks only get shared references to the entries.
+        unsafe impl<K: Sync, V: Sync> Sync for SharedArray<'_, K, V> {}
+
+        let guard = Guard::new();
+        let mut current_array_ptr = self.array.load(Acquire, &guard);
+        while let Some(current_array) = current_array_ptr.as_ref() {
+            self.clear_old_array(current_array, &guard);
+            let shared_array = SharedArray(current_array);
+            (0..current_array.num_buckets())
+                .into_par_iter()
+                .for_each(|index| {
+                    let current_array = shared_array.get();
+                    let guard = Guard::new();
+                    if let Some(reader) = Reader::lock(current_array.bucket(index), &guard) {
+                        let data_block = current_array.data_block(index);
+                        let mut entry_ptr = EntryPtr::new(&guard);
+                        while entry_ptr.move_to_next(*reader, &guard) {
+                            let (k, v) = entry_ptr.get(data_block);
+                            f(k, v);
+                        }
+                    };
+                });
+
+            let new_current_array_ptr = self.array.load(Acquire, &guard);
+            if current_array_ptr.without_tag() == new_current_array_ptr.without_tag() {
+                break;
+            }
+            current_array_ptr = new_current_array_ptr;
+        }
+    }
+}
+
 impl<K, V> HashMap<K, V, RandomState>
 where
     K: Eq + Hash,
@@ -1481,7 +2529,9 @@ where
 
     /// Creates an empty [`HashMap`] with the specified capacity.
     ///
-    /// The actual capacity is equal to or greater than the specified capacity.
+    /// The actual capacity is equal to or greater than the specified capacity. The [`HashMap`]
+    /// may grow before the number of entries reaches the capacity as described in
+    /// [Resize](HashMap#resize).
     ///
     /// # Examples
     ///
@@ -1506,6 +2556,13 @@ where
     V: Clone,
     H: BuildHasher + Clone,
 {
+    /// Clones the [`HashMap`].
+    ///
+    /// ## Locking behavior
+    ///
+    /// Shared locks on buckets are acquired one at a time while the entries are being copied,
+    /// therefore entries inserted or removed by other threads at the same time may or may not be
+    /// reflected in the clone.
     #[inline]
     fn clone(&self) -> Self {
         let self_clone = Self::with_capacity_and_hasher(self.capacity(), self.hasher().clone());
@@ -1596,6 +2653,28 @@ where
     }
 }
 
+impl<K, V, H> Extend<(K, V)> for HashMap<K, V, H>
+where
+    K: Eq + Hash,
+    H: BuildHasher,
+{
+    /// Inserts or updates the supplied key-value pairs.
+    ///
+    /// The value of a key appearing more than once is updated with the last one. The capacity is
+    /// reserved in advance according to the size hint of the iterator, therefore bulk loading does
+    /// not have to repeatedly resize the [`HashMap`]; each key-value pair only locks the bucket
+    /// containing it while being inserted.
+    #[inline]
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        let into_iter = iter.into_iter();
+        let reserved = self.reserve(Self::capacity_from_size_hint(into_iter.size_hint()));
+        into_iter.for_each(|e| {
+            self.upsert(e.0, e.1);
+        });
+        drop(reserved);
+    }
+}
+
 impl<K, V, H> HashTable<K, V, H, (), SEQUENTIAL> for HashMap<K, V, H>
 where
     K: Eq + Hash,
@@ -1631,6 +2710,11 @@ where
 {
     /// Compares two [`HashMap`] instances.
     ///
+    /// Two instances are equal if every entry in either of them is found with an equal value in
+    /// the other. The comparison is not performed on a snapshot of the instances, therefore the
+    /// result is only weakly consistent if either of them is concurrently modified: for instance,
+    /// an entry moved from one key to another during the comparison may be missed.
+    ///
     /// ## Locking behavior
     ///
     /// Shared locks on buckets are acquired when comparing two instances of [`HashMap`], therefore
@@ -1906,6 +2990,19 @@ where
     ///     assert_eq!(o.get(), &11);
     /// };
     /// ```
+    ///
+    /// The returned reference cannot outlive the [`OccupiedEntry`] that keeps the entry locked.
+    ///
+    /// ```compile_fail,E0505
+    /// use scc::HashMap;
+    ///
+    /// let hashmap: HashMap<u64, u32> = HashMap::default();
+    ///
+    /// let o = hashmap.entry(19).or_insert(11);
+    /// let v = o.get();
+    /// drop(o);
+    /// assert_eq!(v, &11);
+    /// ```
     #[inline]
     #[must_use]
     pub fn get(&self) -> &V {
@@ -1935,6 +3032,19 @@ where
     ///
     /// assert_eq!(hashmap.read(&37, |_, v| *v), Some(29));
     /// ```
+    ///
+    /// The returned reference cannot outlive the [`OccupiedEntry`] that keeps the entry locked.
+    ///
+    /// ```compile_fail,E0505
+    /// use scc::HashMap;
+    ///
+    /// let hashmap: HashMap<u64, u32> = HashMap::default();
+    ///
+    /// let mut o = hashmap.entry(37).or_insert(11);
+    /// let v = o.get_mut();
+    /// drop(o);
+    /// *v += 18;
+    /// ```
     #[inline]
     pub fn get_mut(&mut self) -> &mut V {
         &mut self
@@ -2193,6 +3303,131 @@ where
     }
 }
 
+impl BucketStats {
+    /// Returns the number of buckets.
+    #[inline]
+    #[must_use]
+    pub const fn num_buckets(&self) -> usize {
+        self.num_buckets
+    }
+
+    /// Returns the number of entries.
+    #[inline]
+    #[must_use]
+    pub const fn num_entries(&self) -> usize {
+        self.num_entries
+    }
+
+    /// Returns the number of buckets that have run out of slots, and therefore have linked
+    /// buckets attached to them.
+    #[inline]
+    #[must_use]
+    pub const fn num_overflowed_buckets(&self) -> usize {
+        self.num_overflowed_buckets
+    }
+
+    /// Returns the maximum number of linked buckets attached to a single bucket.
+    #[inline]
+    #[must_use]
+    pub const fn max_linked_buckets(&self) -> usize {
+        self.max_linked_buckets
+    }
+
+    /// Returns the average number of entries in a bucket.
+    #[allow(clippy::cast_precision_loss)]
+    #[inline]
+    #[must_use]
+    pub fn avg_entries_per_bucket(&self) -> f64 {
+        if self.num_buckets == 0 {
+            0.0
+        } else {
+            self.num_entries as f64 / self.num_buckets as f64
+        }
+    }
+}
+
+impl HashMapBuilder<RandomState> {
+    /// Creates a new [`HashMapBuilder`] with the default options.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::hash_map::HashMapBuilder;
+    /// use scc::HashMap;
+    ///
+    /// let hashmap: HashMap<u64, u32> = HashMapBuilder::new().build();
+    /// assert_eq!(hashmap.capacity(), 0);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<H: BuildHasher> HashMapBuilder<H> {
+    /// Sets the minimum capacity of the [`HashMap`].
+    ///
+    /// The actual capacity is equal to or greater than the specified capacity, and the
+    /// [`HashMap`] does not shrink below it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::hash_map::HashMapBuilder;
+    /// use scc::HashMap;
+    ///
+    /// let hashmap: HashMap<u64, u32> = HashMapBuilder::new().capacity(1000).build();
+    /// assert_eq!(hashmap.capacity(), 1024);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn capacity(self, capacity: usize) -> Self {
+        Self { capacity, ..self }
+    }
+
+    /// Sets the [`BuildHasher`] of the [`HashMap`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::hash_map::HashMapBuilder;
+    /// use scc::HashMap;
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let hashmap: HashMap<u64, u32, RandomState> = HashMapBuilder::new()
+    ///     .hasher(RandomState::new())
+    ///     .capacity(64)
+    ///     .build();
+    /// assert_eq!(hashmap.capacity(), 64);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn hasher<G: BuildHasher>(self, build_hasher: G) -> HashMapBuilder<G> {
+        HashMapBuilder {
+            capacity: self.capacity,
+            build_hasher,
+        }
+    }
+
+    /// Builds a [`HashMap`] with the collected options.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::hash_map::HashMapBuilder;
+    /// use scc::HashMap;
+    ///
+    /// let hashmap: HashMap<u64, u32> = HashMapBuilder::new().capacity(32).build();
+    /// assert!(hashmap.insert(1, 0).is_ok());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn build<K, V>(self) -> HashMap<K, V, H> {
+        HashMap::with_capacity_and_hasher(self.capacity, self.build_hasher)
+    }
+}
+
 impl<K, V, H> Reserve<'_, K, V, H>
 where
     K: Eq + Hash,
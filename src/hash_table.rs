@@ -699,10 +699,15 @@ where
             let mut bucket = current_array.bucket_mut(index);
 
             // Try to resize the array.
+            //
+            // A bucket outside the sampling range is not looked at when the hash table is sampled,
+            // therefore a bucket storing more than half of `BUCKET_LEN` entries in linked buckets
+            // samples the hash table by itself.
             if resizable
                 && (TYPE != CACHE || current_array.num_entries() < self.maximum_capacity())
-                && current_array.within_sampling_range(index)
                 && bucket.num_entries() >= BUCKET_LEN - 1
+                && (current_array.within_sampling_range(index)
+                    || bucket.num_entries() >= BUCKET_LEN + BUCKET_LEN / 2)
             {
                 self.try_enlarge(current_array, index, bucket.num_entries(), guard);
                 bucket = current_array.bucket_mut(index);
@@ -884,6 +889,33 @@ where
         }
     }
 
+    /// Relocates the bucket at the specified index in the old array to the current array.
+    ///
+    /// Returns `false` if no old array is attached to the current array or the bucket was already
+    /// relocated. The rehashing metadata of the old array is left untouched, therefore the old
+    /// array is only dropped by subsequent calls to `incremental_rehash`.
+    #[cfg(test)]
+    fn migrate_bucket(&self, index: usize) -> bool {
+        let guard = Guard::new();
+        let current_array = self.get_current_array(&guard);
+        let Some(old_array) = current_array.old_array(&guard).as_ref() else {
+            return false;
+        };
+        if let Some(mut locker) = Locker::lock(old_array.bucket_mut(index), &guard) {
+            return self
+                .relocate_bucket::<K, _, false>(
+                    current_array,
+                    old_array,
+                    index,
+                    &mut locker,
+                    &mut (),
+                    &guard,
+                )
+                .is_ok();
+        }
+        false
+    }
+
     /// Relocates a fixed number of buckets from the old array to the current array.
     ///
     /// Returns `true` if `old_array` is null.
@@ -1089,11 +1121,12 @@ where
                     new_capacity
                 }
             } else if estimated_num_entries <= capacity / 16 {
-                // Shrink to fit.
+                // Shrink to fit, or grow up to `32x` if the minimum capacity was increased.
                 estimated_num_entries
                     .max(minimum_capacity)
                     .max(BucketArray::<K, V, L, TYPE>::minimum_capacity())
                     .next_power_of_two()
+                    .min(capacity.saturating_mul(MAX_RESIZE_FACTOR))
             } else {
                 capacity
             };
@@ -1433,3 +1466,83 @@ unsafe impl<K: Eq + Hash + Send, V: Send, L: LruList, const TYPE: char> Send
     for LockedEntry<'_, K, V, L, TYPE>
 {
 }
+
+#[cfg(not(feature = "loom"))]
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::HashMap;
+    use bucket::SEQUENTIAL;
+
+    #[test]
+    fn migrate_bucket() {
+        let workload_size = 128;
+        let hashmap: HashMap<usize, usize> = HashMap::with_capacity(4096);
+        for k in 0..workload_size {
+            assert!(hashmap.insert(k, k).is_ok());
+        }
+
+        // Reserving a large capacity allocates a new array without relocating any entries.
+        let reserved = hashmap.reserve(4096 * 16);
+        assert!(reserved.is_some());
+
+        let guard = Guard::new();
+        let current_array = hashmap.get_current_array(&guard);
+        let old_array = current_array.old_array(&guard).as_ref().unwrap();
+        assert!(old_array.num_buckets() < current_array.num_buckets());
+
+        // Returns the index of the array containing the key; `0` for the old array.
+        let locate = |k: usize| {
+            let hash = hashmap.hash(&k);
+            let partial_hash = BucketArray::<usize, usize, (), SEQUENTIAL>::partial_hash(hash);
+            let mut found = Vec::new();
+            for (i, array) in [old_array, current_array].into_iter().enumerate() {
+                let index = array.calculate_bucket_index(hash);
+                if let Some(entry) = array.bucket(index).search_entry(
+                    array.data_block(index),
+                    &k,
+                    partial_hash,
+                    &guard,
+                ) {
+                    assert_eq!(*entry, (k, k));
+                    found.push(i);
+                }
+            }
+            assert_eq!(found.len(), 1, "{k}");
+            found[0]
+        };
+        for k in 0..workload_size {
+            assert_eq!(locate(k), 0);
+        }
+
+        for index in 0..old_array.num_buckets() {
+            assert!(hashmap.migrate_bucket(index));
+            assert!(!hashmap.migrate_bucket(index));
+            for k in 0..workload_size {
+                let old_index = old_array.calculate_bucket_index(hashmap.hash(&k));
+                assert_eq!(locate(k), usize::from(old_index <= index));
+            }
+        }
+        assert!(current_array.has_old_array());
+
+        // Public methods drop the old array after every bucket is marked relocated.
+        for k in 0..workload_size {
+            assert_eq!(hashmap.read(&k, |_, v| *v), Some(k));
+        }
+        assert!(!current_array.has_old_array());
+        assert!(!hashmap.migrate_bucket(0));
+        drop(reserved);
+    }
+
+    #[test]
+    fn hasher() {
+        let hashmap: HashMap<String, usize> = HashMap::default();
+        for k in 0..64 {
+            let key = k.to_string();
+            let mut hasher = hashmap.hasher().build_hasher();
+            key.hash(&mut hasher);
+            assert_eq!(hasher.finish(), HashTable::hash(&hashmap, &key));
+            assert_eq!(hasher.finish(), HashTable::hash(&hashmap, key.as_str()));
+        }
+    }
+}
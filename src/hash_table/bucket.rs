@@ -12,6 +12,14 @@ use std::sync::atomic::{fence, AtomicU32};
 /// [`Bucket`] is a fixed-size hash table with linear probing.
 ///
 /// `TYPE` is either one of [`SEQUENTIAL`], [`OPTIMISTIC`], or [`CACHE`].
+///
+/// [`Bucket`] is aligned to a cache line so that locking a [`Bucket`] does not invalidate the
+/// cache line of an adjacent one.
+///
+/// Entries are never moved once inserted, since lock-free readers of an [`OPTIMISTIC`] [`Bucket`]
+/// and [`EntryPtr`] instances rely on their positions: removing an entry only clears its bit in
+/// the occupied bitmap, and overflowing entries are stored in a linked list of [`LinkedBucket`]
+/// instances.
 #[repr(align(64))]
 pub struct Bucket<K, V, L: LruList, const TYPE: char> {
     /// The state of the [`Bucket`].
@@ -20,6 +28,10 @@ pub struct Bucket<K, V, L: LruList, const TYPE: char> {
     state: AtomicU32,
 
     /// The number of occupied entries in the [`Bucket`].
+    ///
+    /// It is separate from `state`, therefore overflowing entries stored in [`LinkedBucket`]
+    /// instances can never corrupt the lock bits; insertion panics before the counter wraps
+    /// around.
     num_entries: u32,
 
     /// The metadata of the [`Bucket`].
@@ -68,17 +80,29 @@ pub const OPTIMISTIC: char = 'O';
 pub const CACHE: char = 'C';
 
 /// The size of a [`Bucket`].
+///
+/// The number of slots is not a type parameter of [`Bucket`]: occupied and removed slots are
+/// tracked in `u32` bitmaps, the [`Bucket`] is laid out to fit in one or two cache lines, and the
+/// LRU list and [`EntryPtr`] assume a fixed number of slots. [`Metadata`] and [`LinkedBucket`]
+/// are generic over the number of slots, and are used with [`BUCKET_LEN`] and
+/// [`LINKED_BUCKET_LEN`].
 pub const BUCKET_LEN: usize = u32::BITS as usize;
 
 /// [`DataBlock`] is a type alias of a raw memory chunk of a type-dependent fixed size.
 pub type DataBlock<K, V, const LEN: usize> = [MaybeUninit<(K, V)>; LEN];
 
 /// [`Locker`] owns a [`Bucket`] by holding the exclusive lock on it.
+///
+/// The lock state is stored in the [`Bucket`] rather than being bound to the thread that acquired
+/// it, therefore [`Locker`] is [`Send`] and [`Sync`] as long as the [`Bucket`] is, and the borrow
+/// of the [`Bucket`] prevents the [`Locker`] from outliving it.
 pub struct Locker<'g, K, V, L: LruList, const TYPE: char> {
     bucket: &'g mut Bucket<K, V, L, TYPE>,
 }
 
-/// [`Locker`] owns a [`Bucket`] by holding a shared lock on it.
+/// [`Reader`] owns a [`Bucket`] by holding a shared lock on it.
+///
+/// [`Reader`] is [`Send`] and [`Sync`] under the same conditions as [`Locker`].
 pub struct Reader<'g, K, V, L: LruList, const TYPE: char> {
     bucket: &'g Bucket<K, V, L, TYPE>,
 }
@@ -137,6 +161,45 @@ impl<K, V, L: LruList, const TYPE: char> Bucket<K, V, L, TYPE> {
         self.num_entries as usize
     }
 
+    /// Returns the number of [`LinkedBucket`] instances attached to the [`Bucket`].
+    #[inline]
+    pub(crate) fn num_linked_buckets(&self, guard: &Guard) -> usize {
+        let mut num_linked_buckets = 0;
+        let mut link_ptr = self.metadata.link.load(Acquire, guard);
+        while let Some(link) = link_ptr.as_ref() {
+            num_linked_buckets += 1;
+            link_ptr = link.metadata.link.load(Acquire, guard);
+        }
+        num_linked_buckets
+    }
+
+    /// Counts the occupied and reachable slots in the [`Bucket`] and its [`LinkedBucket`]
+    /// instances.
+    ///
+    /// Unlike [`Self::num_entries`], it does not rely on the entry counter, therefore tests can
+    /// check if the counter is consistent with the slots.
+    #[cfg(test)]
+    pub(crate) fn actual_occupancy(&self, guard: &Guard) -> usize {
+        fn count<K, V, const TYPE: char, const LEN: usize>(
+            metadata: &Metadata<K, V, LEN>,
+        ) -> usize {
+            let bitmap = if TYPE == OPTIMISTIC {
+                metadata.occupied_bitmap & (!metadata.removed_bitmap_or_lru_tail)
+            } else {
+                metadata.occupied_bitmap
+            };
+            bitmap.count_ones() as usize
+        }
+
+        let mut actual_occupancy = count::<K, V, TYPE, BUCKET_LEN>(&self.metadata);
+        let mut link_ptr = self.metadata.link.load(Acquire, guard);
+        while let Some(link) = link_ptr.as_ref() {
+            actual_occupancy += count::<K, V, TYPE, LINKED_BUCKET_LEN>(&link.metadata);
+            link_ptr = link.metadata.link.load(Acquire, guard);
+        }
+        actual_occupancy
+    }
+
     /// Returns `true` if the [`Bucket`] needs to be rebuilt.
     ///
     /// If `TYPE == OPTIMISTIC`, removed entries are not dropped, still occupying the slots,
@@ -648,14 +711,7 @@ impl<K: Eq, V, L: LruList, const TYPE: char> Bucket<K, V, L, TYPE> {
             fence(Acquire);
         }
 
-        // Expect that the loop is vectorized by the compiler.
-        let mut matching: u32 = 0;
-        for i in 0..LEN {
-            if metadata.partial_hash_array[i] == partial_hash {
-                matching |= 1_u32 << i;
-            }
-        }
-        bitmap &= matching;
+        bitmap &= match_partial_hash(&metadata.partial_hash_array, partial_hash);
 
         let mut offset = bitmap.trailing_zeros();
         while offset != u32::BITS {
@@ -875,6 +931,7 @@ impl<'g, K, V, L: LruList, const TYPE: char> Locker<'g, K, V, L, TYPE> {
             if let Ok(locker) = Self::try_lock(unsafe { &mut *bucket_ptr }, guard) {
                 return locker;
             }
+            on_contended();
             if let Ok(locker) = unsafe { &*bucket_ptr }.wait_queue.wait_sync(|| {
                 // Mark that there is a waiting thread.
                 bucket.state.fetch_or(WAITING, Release);
@@ -886,6 +943,9 @@ impl<'g, K, V, L: LruList, const TYPE: char> Locker<'g, K, V, L, TYPE> {
     }
 
     /// Tries to lock the [`Bucket`].
+    ///
+    /// It makes a single attempt to acquire the lock, and returns `Err(())` on failure without
+    /// spinning or pushing an entry to the wait queue.
     #[inline]
     pub(crate) fn try_lock(
         bucket: &'g mut Bucket<K, V, L, TYPE>,
@@ -900,6 +960,7 @@ impl<'g, K, V, L: LruList, const TYPE: char> Locker<'g, K, V, L, TYPE> {
             .compare_exchange(current, current | LOCK, Acquire, Relaxed)
             .is_ok()
         {
+            on_acquired();
             Ok(Some(Locker { bucket }))
         } else {
             Err(())
@@ -917,6 +978,7 @@ impl<'g, K, V, L: LruList, const TYPE: char> Locker<'g, K, V, L, TYPE> {
         if let Ok(locker) = Self::try_lock(unsafe { &mut *bucket_ptr }, guard) {
             return Ok(locker);
         }
+        on_contended();
         unsafe { &*bucket_ptr }
             .wait_queue
             .push_async_entry(async_wait, || {
@@ -957,6 +1019,10 @@ impl<K, V, L: LruList, const TYPE: char> Drop for Locker<'_, K, V, L, TYPE> {
         }
 
         if (current & WAITING) == WAITING {
+            // A waiting thread pushes its entry before setting `WAITING` with `Release`, and then
+            // retries to acquire the lock. Either the retry observes the lock released, or the
+            // entry is guaranteed to be visible to `signal` after this fence.
+            fence(Acquire);
             self.bucket.wait_queue.signal();
         }
     }
@@ -975,6 +1041,7 @@ impl<'g, K, V, L: LruList, const TYPE: char> Reader<'g, K, V, L, TYPE> {
             if let Ok(reader) = Self::try_lock(bucket, guard) {
                 return reader;
             }
+            on_contended();
             if let Ok(reader) = bucket.wait_queue.wait_sync(|| {
                 // Mark that there is a waiting thread.
                 bucket.state.fetch_or(WAITING, Release);
@@ -995,6 +1062,7 @@ impl<'g, K, V, L: LruList, const TYPE: char> Reader<'g, K, V, L, TYPE> {
         if let Ok(reader) = Self::try_lock(bucket, guard) {
             return Ok(reader);
         }
+        on_contended();
         bucket.wait_queue.push_async_entry(async_wait, || {
             // Mark that there is a waiting thread.
             bucket.state.fetch_or(WAITING, Release);
@@ -1003,12 +1071,17 @@ impl<'g, K, V, L: LruList, const TYPE: char> Reader<'g, K, V, L, TYPE> {
     }
 
     /// Tries to lock the [`Bucket`].
+    ///
+    /// It makes a single attempt to acquire the lock, and returns `Err(())` on failure without
+    /// spinning or pushing an entry to the wait queue.
     pub(crate) fn try_lock(
         bucket: &'g Bucket<K, V, L, TYPE>,
         _guard: &'g Guard,
     ) -> Result<Option<Reader<'g, K, V, L, TYPE>>, ()> {
         let current = bucket.state.load(Relaxed);
         if (current & LOCK_MASK) >= SLOCK_MAX {
+            // Exclusively locked, or the shared lock counter is saturated: incrementing it would
+            // set the `LOCK` bit.
             return Err(());
         }
         if (current & KILLED) == KILLED {
@@ -1019,6 +1092,7 @@ impl<'g, K, V, L: LruList, const TYPE: char> Reader<'g, K, V, L, TYPE> {
             .compare_exchange(current, current + 1, Acquire, Relaxed)
             .is_ok()
         {
+            on_acquired();
             Ok(Some(Reader { bucket }))
         } else {
             Err(())
@@ -1026,18 +1100,30 @@ impl<'g, K, V, L: LruList, const TYPE: char> Reader<'g, K, V, L, TYPE> {
     }
 
     /// Releases the lock.
+    ///
+    /// Waiting threads are woken up only when the last shared lock is released, or when the shared
+    /// lock counter is no longer saturated, since they cannot acquire the lock otherwise.
     #[inline]
     pub(super) fn release(bucket: &Bucket<K, V, L, TYPE>) {
         let mut current = bucket.state.load(Relaxed);
         loop {
-            let wakeup = (current & WAITING) == WAITING;
-            let next = (current - 1) & (!WAITING);
+            let num_readers = current & LOCK_MASK;
+            let wakeup =
+                (current & WAITING) == WAITING && (num_readers == 1 || num_readers == SLOCK_MAX);
+            let next = if wakeup {
+                (current - 1) & (!WAITING)
+            } else {
+                current - 1
+            };
             match bucket
                 .state
                 .compare_exchange_weak(current, next, Release, Relaxed)
             {
                 Ok(_) => {
                     if wakeup {
+                        // Synchronize with the waiting thread that set `WAITING` after pushing
+                        // its entry.
+                        fence(Acquire);
                         bucket.wait_queue.signal();
                     }
                     break;
@@ -1225,19 +1311,142 @@ impl<K, V, const LEN: usize> Drop for LinkedBucket<K, V, LEN> {
     }
 }
 
+/// Reports that a [`Bucket`] was locked.
+///
+/// It is a no-op unless the `metrics` feature is enabled.
+#[inline]
+fn on_acquired() {
+    #[cfg(feature = "metrics")]
+    crate::metrics::record(crate::metrics::LockEvent::Acquired);
+}
+
+/// Reports that locking a [`Bucket`] has to wait in the wait queue.
+///
+/// It is a no-op unless the `metrics` feature is enabled.
+#[inline]
+fn on_contended() {
+    #[cfg(feature = "metrics")]
+    crate::metrics::record(crate::metrics::LockEvent::Contended);
+}
+
+/// Returns a bitmap of the slots whose partial hash value is equal to `partial_hash`.
+///
+/// With the `simd` feature on `x86_64`, the partial hash array is compared in chunks of `16`
+/// slots using `SSE2` instructions which are always available on the architecture.
+#[allow(clippy::inline_always)]
+#[inline(always)]
+fn match_partial_hash<const LEN: usize>(partial_hash_array: &[u8; LEN], partial_hash: u8) -> u32 {
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    if LEN % 16 == 0 {
+        return match_partial_hash_sse2(partial_hash_array, partial_hash);
+    }
+    match_partial_hash_scalar(partial_hash_array, partial_hash)
+}
+
+/// Returns a bitmap of the slots whose partial hash value is equal to `partial_hash` by
+/// comparing each slot.
+#[allow(clippy::inline_always)]
+#[inline(always)]
+fn match_partial_hash_scalar<const LEN: usize>(
+    partial_hash_array: &[u8; LEN],
+    partial_hash: u8,
+) -> u32 {
+    // Expect that the loop is vectorized by the compiler.
+    let mut matching: u32 = 0;
+    for (i, p) in partial_hash_array.iter().enumerate() {
+        if *p == partial_hash {
+            matching |= 1_u32 << i;
+        }
+    }
+    matching
+}
+
+/// Returns a bitmap of the slots whose partial hash value is equal to `partial_hash` by
+/// comparing `16` slots at once.
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+#[allow(
+    clippy::cast_ptr_alignment,
+    clippy::cast_sign_loss,
+    clippy::inline_always
+)]
+#[inline(always)]
+fn match_partial_hash_sse2<const LEN: usize>(
+    partial_hash_array: &[u8; LEN],
+    partial_hash: u8,
+) -> u32 {
+    use std::arch::x86_64::{
+        __m128i, _mm_cmpeq_epi8, _mm_loadu_si128, _mm_movemask_epi8, _mm_set1_epi8,
+    };
+
+    debug_assert_eq!(LEN % 16, 0);
+    let mut matching: u32 = 0;
+    unsafe {
+        let needle = _mm_set1_epi8(i8::from_ne_bytes([partial_hash]));
+        for chunk in 0..LEN / 16 {
+            // `_mm_loadu_si128` does not require the address to be aligned.
+            let slots = _mm_loadu_si128(
+                partial_hash_array
+                    .as_ptr()
+                    .add(chunk * 16)
+                    .cast::<__m128i>(),
+            );
+            // Only the lower `16` bits of the mask can be set.
+            let mask = _mm_movemask_epi8(_mm_cmpeq_epi8(slots, needle)) as u32;
+            matching |= mask << (chunk * 16);
+        }
+    }
+    matching
+}
+
 #[cfg(not(feature = "loom"))]
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::wait_queue::DeriveAsyncWait;
     use proptest::prelude::*;
+    use std::collections::BTreeSet;
     use std::pin::Pin;
+    use std::rc::Rc;
+    use std::time::Duration;
     use tokio::sync::Barrier;
 
+    // A `Locker` or `Reader` can be sent to another thread as the lock state is not bound to the
+    // thread that acquired it, however the auto traits must not be derived if `K` or `V` is
+    // thread-local.
+    static_assertions::assert_impl_all!(Bucket<String, String, (), SEQUENTIAL>: Send, Sync);
+    static_assertions::assert_not_impl_any!(Bucket<Rc<String>, String, (), SEQUENTIAL>: Send, Sync);
+    static_assertions::assert_impl_all!(Locker<'static, String, String, (), SEQUENTIAL>: Send, Sync);
+    static_assertions::assert_not_impl_any!(Locker<'static, Rc<String>, String, (), SEQUENTIAL>: Send, Sync);
+    static_assertions::assert_not_impl_any!(Locker<'static, String, *const String, (), SEQUENTIAL>: Send, Sync);
+    static_assertions::assert_impl_all!(Reader<'static, String, String, (), OPTIMISTIC>: Send, Sync);
+    static_assertions::assert_not_impl_any!(Reader<'static, Rc<String>, String, (), OPTIMISTIC>: Send, Sync);
+    static_assertions::assert_not_impl_any!(Reader<'static, String, *const String, (), OPTIMISTIC>: Send, Sync);
+
+    // A `Bucket` without an LRU list fits in a single cache line regardless of `K` and `V`.
+    #[cfg(not(miri))]
+    static_assertions::assert_eq_size!(Bucket<u64, bool, (), SEQUENTIAL>, [u8; BUCKET_LEN * 2]);
+    #[cfg(not(miri))]
+    static_assertions::assert_eq_size!(Bucket<String, String, (), SEQUENTIAL>, [u8; BUCKET_LEN * 2]);
     #[cfg(not(miri))]
     static_assertions::assert_eq_size!(Bucket<String, String, (), OPTIMISTIC>, [u8; BUCKET_LEN * 2]);
     #[cfg(not(miri))]
     static_assertions::assert_eq_size!(Bucket<String, String, DoublyLinkedList, CACHE>, [u8; BUCKET_LEN * 4]);
+    static_assertions::const_assert_eq!(
+        std::mem::align_of::<Bucket<u64, bool, (), SEQUENTIAL>>(),
+        64
+    );
+    static_assertions::const_assert_eq!(
+        std::mem::align_of::<Bucket<u8, u8, DoublyLinkedList, CACHE>>(),
+        64
+    );
+
+    // The state bits must not overlap, and the shared lock counter must stay below `LOCK`.
+    static_assertions::const_assert_eq!(LOCK_MASK, (1_u32 << EPOCH_POS) - 1);
+    static_assertions::const_assert_eq!(SLOCK_MAX + 1, LOCK);
+    static_assertions::const_assert_eq!(KILLED & (WAITING | EPOCH_MASK | LOCK_MASK), 0);
+    static_assertions::const_assert_eq!(WAITING & (EPOCH_MASK | LOCK_MASK), 0);
+    static_assertions::const_assert_eq!(EPOCH_MASK & LOCK_MASK, 0);
+    static_assertions::const_assert_eq!(KILLED | WAITING | EPOCH_MASK | LOCK_MASK, u32::MAX);
 
     fn default_bucket<K: Eq, V, L: LruList, const TYPE: char>() -> Bucket<K, V, L, TYPE> {
         Bucket {
@@ -1249,6 +1458,17 @@ mod test {
         }
     }
 
+    fn shared_bucket() -> Shared<Bucket<usize, usize, (), SEQUENTIAL>> {
+        Shared::new(default_bucket())
+    }
+
+    #[allow(clippy::mut_from_ref)]
+    fn bucket_mut(
+        bucket: &Shared<Bucket<usize, usize, (), SEQUENTIAL>>,
+    ) -> &mut Bucket<usize, usize, (), SEQUENTIAL> {
+        unsafe { &mut *bucket.as_ptr().cast_mut() }
+    }
+
     proptest! {
         #[cfg_attr(miri, ignore)]
         #[test]
@@ -1375,6 +1595,45 @@ mod test {
             assert_eq!(bucket.metadata.removed_bitmap_or_lru_tail, 0);
         }
 
+        #[cfg_attr(miri, ignore)]
+        #[test]
+        fn occupancy(ops in prop::collection::vec((any::<bool>(), 0..BUCKET_LEN * 3), 0..512)) {
+            check_occupancy::<SEQUENTIAL>(&ops);
+            check_occupancy::<OPTIMISTIC>(&ops);
+        }
+
+    }
+
+    /// Applies the insert or remove operations, and checks if the entry counter matches the
+    /// number of occupied slots after each of them.
+    fn check_occupancy<const TYPE: char>(ops: &[(bool, usize)]) {
+        let mut data_block: DataBlock<usize, usize, BUCKET_LEN> =
+            unsafe { MaybeUninit::uninit().assume_init() };
+        let mut bucket: Bucket<usize, usize, (), TYPE> = default_bucket();
+        let mut expected = BTreeSet::new();
+        for &(insert, k) in ops {
+            let guard = Guard::new();
+            let mut locker = Locker::lock(&mut bucket, &guard).unwrap();
+            let partial_hash = u8::try_from(k % 4).unwrap();
+            let mut entry_ptr = locker.get_entry_ptr(&data_block, &k, partial_hash, &guard);
+            assert_eq!(entry_ptr.is_valid(), expected.contains(&k));
+            if insert && !entry_ptr.is_valid() {
+                locker.insert_with(&mut data_block, partial_hash, || (k, k), &guard);
+                expected.insert(k);
+            } else if !insert && entry_ptr.is_valid() {
+                if TYPE == OPTIMISTIC {
+                    locker.mark_removed(&mut entry_ptr, &guard);
+                } else {
+                    assert_eq!(
+                        locker.remove(&mut data_block, &mut entry_ptr, &guard),
+                        (k, k)
+                    );
+                }
+                expected.remove(&k);
+            }
+            assert_eq!(locker.num_entries(), locker.actual_occupancy(&guard));
+            assert_eq!(locker.num_entries(), expected.len());
+        }
     }
 
     #[cfg_attr(miri, ignore)]
@@ -1570,4 +1829,783 @@ mod test {
             assert!(r.is_ok());
         }
     }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn bucket_lock_async_cancel() {
+        async fn lock_async(bucket: &Shared<Bucket<usize, usize, (), SEQUENTIAL>>) {
+            loop {
+                let mut async_wait = AsyncWait::default();
+                let mut async_wait_pinned = Pin::new(&mut async_wait);
+                {
+                    let guard = Guard::new();
+                    if let Ok(locker) = Locker::try_lock_or_wait(
+                        bucket_mut(bucket),
+                        async_wait_pinned.derive().unwrap(),
+                        &guard,
+                    ) {
+                        assert!(locker.is_some());
+                        break;
+                    };
+                }
+                async_wait_pinned.await;
+            }
+        }
+
+        let bucket = shared_bucket();
+        let guard = Guard::new();
+        let locker = Locker::lock(bucket_mut(&bucket), &guard).unwrap();
+
+        // The future waits for the lock to be released, and is dropped while waiting.
+        let mut lock_future = Box::pin(lock_async(&bucket));
+        let waker = futures::task::noop_waker();
+        let mut context = std::task::Context::from_waker(&waker);
+        assert!(std::future::Future::poll(lock_future.as_mut(), &mut context).is_pending());
+        assert_eq!(bucket.state.load(Relaxed) & WAITING, WAITING);
+        assert_eq!(unsafe { bucket.wait_queue.num_entries() }, 1);
+        drop(lock_future);
+        assert_eq!(unsafe { bucket.wait_queue.num_entries() }, 0);
+        drop(locker);
+
+        assert_eq!(bucket.state.load(Relaxed) & LOCK_MASK, 0);
+        assert!(Locker::try_lock(bucket_mut(&bucket), &guard)
+            .unwrap()
+            .is_some());
+    }
+
+    #[cfg(feature = "metrics")]
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn bucket_lock_metrics() {
+        use crate::metrics::{self, LockEvent};
+        use std::cell::Cell;
+
+        // Events are counted per thread as the hook is shared with the other tests.
+        thread_local! {
+            static TRACKED: Cell<bool> = const { Cell::new(false) };
+            static ACQUIRED: Cell<usize> = const { Cell::new(0) };
+            static CONTENDED: Cell<usize> = const { Cell::new(0) };
+        }
+        static NUM_CONTENDED_THREADS: AtomicU32 = AtomicU32::new(0);
+        metrics::set_hook(|event| match event {
+            LockEvent::Acquired => ACQUIRED.with(|c| c.set(c.get() + 1)),
+            LockEvent::Contended => {
+                if CONTENDED.with(|c| c.replace(c.get() + 1)) == 0 && TRACKED.with(Cell::get) {
+                    NUM_CONTENDED_THREADS.fetch_add(1, Relaxed);
+                }
+            }
+        });
+
+        let num_threads = 4;
+        let bucket = shared_bucket();
+        let guard = Guard::new();
+        let locker = Locker::lock(bucket_mut(&bucket), &guard).unwrap();
+        assert_eq!(ACQUIRED.with(Cell::get), 1);
+        assert_eq!(CONTENDED.with(Cell::get), 0);
+
+        let mut threads = Vec::with_capacity(num_threads);
+        for _ in 0..num_threads {
+            let bucket_clone = bucket.clone();
+            threads.push(std::thread::spawn(move || {
+                TRACKED.with(|t| t.set(true));
+                let guard = Guard::new();
+                let locker = Locker::lock(bucket_mut(&bucket_clone), &guard);
+                assert!(locker.is_some());
+                (ACQUIRED.with(Cell::get), CONTENDED.with(Cell::get))
+            }));
+        }
+
+        // Every thread has to wait for the lock to be released.
+        while NUM_CONTENDED_THREADS.load(Relaxed) != u32::try_from(num_threads).unwrap() {
+            std::thread::yield_now();
+        }
+        drop(locker);
+        for thread in threads {
+            let (acquired, contended) = thread.join().unwrap();
+            assert_eq!(acquired, 1);
+            assert_ne!(contended, 0);
+        }
+
+        let reader = Reader::lock(&*bucket, &guard);
+        assert!(reader.is_some());
+        assert_eq!(ACQUIRED.with(Cell::get), 2);
+        assert!(matches!(Reader::try_lock(&*bucket, &guard), Ok(Some(_))));
+        assert_eq!(ACQUIRED.with(Cell::get), 3);
+        assert_eq!(CONTENDED.with(Cell::get), 0);
+        metrics::clear_hook();
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn bucket_lock_shared() {
+        let num_threads = 8;
+        let barrier = Shared::new(std::sync::Barrier::new(num_threads));
+        let bucket = shared_bucket();
+        let data: Shared<[AtomicU32; 2]> = Shared::new([AtomicU32::new(0), AtomicU32::new(0)]);
+
+        {
+            let guard = Guard::new();
+            let reader = Reader::lock(&*bucket, &guard).unwrap();
+            let another_reader = Reader::try_lock(&*bucket, &guard);
+            assert!(matches!(another_reader, Ok(Some(_))));
+            assert_eq!(bucket.state.load(Relaxed) & LOCK_MASK, 2);
+
+            // Writers must back off while readers hold the lock.
+            assert!(Locker::try_lock(bucket_mut(&bucket), &guard).is_err());
+            drop(another_reader);
+            drop(reader);
+            assert_eq!(bucket.state.load(Relaxed) & LOCK_MASK, 0);
+        }
+
+        let mut thread_handles = Vec::with_capacity(num_threads);
+        for thread_id in 0..num_threads {
+            let barrier_clone = barrier.clone();
+            let bucket_clone = bucket.clone();
+            let data_clone = data.clone();
+            thread_handles.push(std::thread::spawn(move || {
+                barrier_clone.wait();
+                let guard = Guard::new();
+                for i in 0..4096_u32 {
+                    if thread_id % 2 == 0 {
+                        let locker = Locker::lock(bucket_mut(&bucket_clone), &guard).unwrap();
+                        assert_eq!(locker.state.load(Relaxed) & LOCK_MASK, LOCK);
+                        data_clone[0].store(i, Relaxed);
+                        data_clone[1].store(i, Relaxed);
+                    } else {
+                        let reader = Reader::lock(&*bucket_clone, &guard).unwrap();
+                        assert_eq!(reader.state.load(Relaxed) & LOCK, 0);
+                        let first = data_clone[0].load(Relaxed);
+                        let second = data_clone[1].load(Relaxed);
+                        assert_eq!(first, second);
+                    }
+                }
+            }));
+        }
+        for t in thread_handles {
+            assert!(t.join().is_ok());
+        }
+        assert_eq!(bucket.state.load(Relaxed) & (LOCK_MASK | WAITING), 0);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn bucket_lock_saturation() {
+        let num_threads = 8;
+        for _ in 0..64 {
+            let barrier = Shared::new(std::sync::Barrier::new(num_threads));
+            let bucket = shared_bucket();
+
+            // Pretend that `SLOCK_MAX - 1` readers are holding the lock.
+            bucket.state.store(SLOCK_MAX - 1, Relaxed);
+
+            let mut thread_handles = Vec::with_capacity(num_threads);
+            for _ in 0..num_threads {
+                let barrier_clone = barrier.clone();
+                let bucket_clone = bucket.clone();
+                thread_handles.push(std::thread::spawn(move || {
+                    barrier_clone.wait();
+                    let guard = Guard::new();
+                    loop {
+                        let result = Reader::try_lock(&*bucket_clone, &guard);
+                        assert_eq!(bucket_clone.state.load(Relaxed) & LOCK, 0);
+                        if let Ok(reader) = result {
+                            // Keep the lock in order to let others observe the saturated state.
+                            forget(reader);
+                            return true;
+                        }
+                        if bucket_clone.state.load(Relaxed) & LOCK_MASK == SLOCK_MAX {
+                            return false;
+                        }
+                    }
+                }));
+            }
+            let num_acquired = thread_handles
+                .into_iter()
+                .filter_map(|t| t.join().ok())
+                .filter(|acquired| *acquired)
+                .count();
+            assert_eq!(num_acquired, 1);
+            assert_eq!(bucket.state.load(Relaxed), SLOCK_MAX);
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn bucket_try_lock() {
+        let num_threads = 4;
+        let num_iters = 4096;
+        let bucket = shared_bucket();
+        let num_failed = Shared::new(AtomicU32::new(0));
+        let guard = Guard::new();
+        let locker = Locker::lock(bucket_mut(&bucket), &guard).unwrap();
+
+        let mut thread_handles = Vec::with_capacity(num_threads);
+        for _ in 0..num_threads {
+            let bucket_clone = bucket.clone();
+            let num_failed_clone = num_failed.clone();
+            thread_handles.push(std::thread::spawn(move || {
+                let guard = Guard::new();
+                for _ in 0..num_iters {
+                    match Locker::try_lock(bucket_mut(&bucket_clone), &guard) {
+                        Ok(Some(locker)) => {
+                            for _ in 0..16 {
+                                std::hint::spin_loop();
+                            }
+                            drop(locker);
+                        }
+                        Ok(None) => unreachable!(),
+                        Err(()) => {
+                            num_failed_clone.fetch_add(1, Relaxed);
+                        }
+                    }
+                }
+            }));
+        }
+
+        // Contended attempts fail without ever waiting.
+        while num_failed.load(Relaxed) < num_threads.try_into().unwrap() {
+            assert!(Locker::try_lock(bucket_mut(&bucket), &guard).is_err());
+            assert!(Reader::try_lock(&*bucket, &guard).is_err());
+            std::thread::yield_now();
+        }
+        assert_eq!(bucket.state.load(Relaxed) & WAITING, 0);
+        drop(locker);
+
+        for thread_handle in thread_handles {
+            assert!(thread_handle.join().is_ok());
+        }
+        assert_eq!(bucket.state.load(Relaxed) & (LOCK_MASK | WAITING), 0);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn bucket_lock_wakeup() {
+        let num_readers = 8;
+        let bucket = shared_bucket();
+        let guard = Guard::new();
+        let mut readers: Vec<_> = (0..num_readers)
+            .map(|_| Reader::lock(&*bucket, &guard).unwrap())
+            .collect();
+
+        let bucket_clone = bucket.clone();
+        let thread_handle = std::thread::spawn(move || {
+            let guard = Guard::new();
+            let locker = Locker::lock(bucket_mut(&bucket_clone), &guard);
+            assert!(locker.is_some());
+        });
+        while bucket.state.load(Relaxed) & WAITING == 0 {
+            std::thread::yield_now();
+        }
+
+        // The waiting thread is not woken up until the last reader releases the lock.
+        while readers.len() > 1 {
+            drop(readers.pop());
+            assert_eq!(bucket.state.load(Relaxed) & WAITING, WAITING);
+        }
+        drop(readers.pop());
+        assert!(thread_handle.join().is_ok());
+        assert_eq!(bucket.state.load(Relaxed) & (LOCK_MASK | WAITING), 0);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn bucket_lock_saturation_wakeup() {
+        let bucket = shared_bucket();
+
+        // Pretend that `SLOCK_MAX` readers are holding the lock.
+        bucket.state.store(SLOCK_MAX, Relaxed);
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let bucket_clone = bucket.clone();
+        let thread_handle = std::thread::spawn(move || {
+            let guard = Guard::new();
+            let reader = Reader::lock(&*bucket_clone, &guard);
+            assert!(reader.is_some());
+            forget(reader);
+            sender.send(()).unwrap();
+        });
+        while bucket.state.load(Relaxed) & WAITING == 0 {
+            std::thread::yield_now();
+        }
+
+        // The waiting reader is woken up as soon as one of the readers releases the lock.
+        Reader::release(&*bucket);
+        assert!(receiver.recv_timeout(Duration::from_secs(60)).is_ok());
+        assert!(thread_handle.join().is_ok());
+        assert_eq!(
+            bucket.state.load(Relaxed) & (LOCK_MASK | WAITING),
+            SLOCK_MAX
+        );
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn bucket_lock_wakeup_mixed() {
+        let num_readers = 4;
+        let num_writers = 4_u32;
+        let bucket = shared_bucket();
+        let barrier = Shared::new(std::sync::Barrier::new(num_readers));
+        let num_writers_locked = Shared::new(AtomicU32::new(0));
+        let guard = Guard::new();
+        let locker = Locker::lock(bucket_mut(&bucket), &guard).unwrap();
+
+        let mut thread_handles = Vec::with_capacity(num_readers + num_writers as usize);
+        for _ in 0..num_readers {
+            let bucket_clone = bucket.clone();
+            let barrier_clone = barrier.clone();
+            thread_handles.push(std::thread::spawn(move || {
+                let guard = Guard::new();
+                let reader = Reader::lock(&*bucket_clone, &guard).unwrap();
+                // All the readers have to hold the shared lock at the same time.
+                barrier_clone.wait();
+                drop(reader);
+            }));
+        }
+        for _ in 0..num_writers {
+            let bucket_clone = bucket.clone();
+            let num_writers_locked_clone = num_writers_locked.clone();
+            thread_handles.push(std::thread::spawn(move || {
+                let guard = Guard::new();
+                let locker = Locker::lock(bucket_mut(&bucket_clone), &guard);
+                assert!(locker.is_some());
+                num_writers_locked_clone.fetch_add(1, Relaxed);
+            }));
+        }
+        while bucket.state.load(Relaxed) & WAITING == 0 {
+            std::thread::yield_now();
+        }
+
+        drop(locker);
+        for thread_handle in thread_handles {
+            assert!(thread_handle.join().is_ok());
+        }
+        assert_eq!(num_writers_locked.load(Relaxed), num_writers);
+        assert_eq!(bucket.state.load(Relaxed) & (LOCK_MASK | WAITING), 0);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn bucket_lock_unlock_race() {
+        let num_threads = 8;
+        let num_iters = 4096;
+        let bucket = shared_bucket();
+        let barrier = Shared::new(std::sync::Barrier::new(num_threads));
+        let (sender, receiver) = std::sync::mpsc::channel();
+        for i in 0..num_threads {
+            let bucket_clone = bucket.clone();
+            let barrier_clone = barrier.clone();
+            let sender = sender.clone();
+            std::thread::spawn(move || {
+                let guard = Guard::new();
+                barrier_clone.wait();
+                for j in 0..num_iters {
+                    // Short critical sections make unlocking race with waiters enqueueing.
+                    if (i + j) % 4 == 0 {
+                        let reader = Reader::lock(&*bucket_clone, &guard);
+                        assert!(reader.is_some());
+                        std::hint::spin_loop();
+                    } else {
+                        let locker = Locker::lock(bucket_mut(&bucket_clone), &guard);
+                        assert!(locker.is_some());
+                        std::hint::spin_loop();
+                    }
+                }
+                sender.send(()).unwrap();
+            });
+        }
+
+        // A lost wakeup leaves a thread sleeping forever.
+        for _ in 0..num_threads {
+            assert!(receiver.recv_timeout(Duration::from_secs(60)).is_ok());
+        }
+        assert_eq!(bucket.state.load(Relaxed) & (LOCK_MASK | WAITING), 0);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn insert_overflow() {
+        let mut data_block: DataBlock<usize, String, BUCKET_LEN> =
+            unsafe { MaybeUninit::uninit().assume_init() };
+        let mut bucket: Bucket<usize, String, (), SEQUENTIAL> = default_bucket();
+        let guard = Guard::new();
+        let mut locker = Locker::lock(&mut bucket, &guard).unwrap();
+
+        // `0` is a valid partial hash value.
+        for k in 0..=(BUCKET_LEN + LINKED_BUCKET_LEN * 2) {
+            let partial_hash = u8::try_from(k % 2).unwrap();
+            let entry_ptr =
+                locker.insert_with(&mut data_block, partial_hash, || (k, k.to_string()), &guard);
+            assert_eq!(entry_ptr.partial_hash(&*locker), partial_hash);
+            assert_eq!(locker.num_entries(), k + 1);
+            let expected_links = if k < BUCKET_LEN {
+                0
+            } else {
+                (k - BUCKET_LEN) / LINKED_BUCKET_LEN + 1
+            };
+            assert_eq!(locker.num_linked_buckets(&guard), expected_links);
+        }
+        assert_eq!(locker.metadata.occupied_bitmap, u32::MAX);
+
+        for k in 0..=(BUCKET_LEN + LINKED_BUCKET_LEN * 2) {
+            let partial_hash = u8::try_from(k % 2).unwrap();
+            let entry = locker.search_entry(&data_block, &k, partial_hash, &guard);
+            assert_eq!(entry, Some(&(k, k.to_string())));
+        }
+        locker.drop_entries(&mut data_block);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn search_partial_hash() {
+        let mut data_block: DataBlock<usize, usize, BUCKET_LEN> =
+            unsafe { MaybeUninit::uninit().assume_init() };
+        let mut bucket: Bucket<usize, usize, (), SEQUENTIAL> = default_bucket();
+        let guard = Guard::new();
+        let mut locker = Locker::lock(&mut bucket, &guard).unwrap();
+
+        // All the entries share the same partial hash value.
+        for k in 0..=BUCKET_LEN {
+            locker.insert_with(&mut data_block, 7, || (k, k * 2), &guard);
+        }
+
+        // Inline hit.
+        assert_eq!(
+            locker.search_entry(&data_block, &3, 7, &guard),
+            Some(&(3, 6))
+        );
+
+        // Hit only in the linked bucket.
+        assert!(!locker.metadata.link.is_null(Relaxed));
+        assert_eq!(
+            locker.search_entry(&data_block, &BUCKET_LEN, 7, &guard),
+            Some(&(BUCKET_LEN, BUCKET_LEN * 2))
+        );
+        assert!(locker
+            .get_entry_ptr(&data_block, &BUCKET_LEN, 7, &guard)
+            .is_valid());
+
+        // A partial hash collision without a matching key.
+        assert!(locker
+            .search_entry(&data_block, &(BUCKET_LEN + 1), 7, &guard)
+            .is_none());
+        assert!(!locker
+            .get_entry_ptr(&data_block, &(BUCKET_LEN + 1), 7, &guard)
+            .is_valid());
+
+        // A matching key with a different partial hash value cannot be found.
+        assert!(locker.search_entry(&data_block, &3, 8, &guard).is_none());
+        assert!(!locker.get_entry_ptr(&data_block, &3, 8, &guard).is_valid());
+
+        locker.drop_entries(&mut data_block);
+    }
+
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    #[test]
+    fn match_partial_hash_simd() {
+        for _ in 0..4096 {
+            // A narrow range of values makes multiple matches frequent.
+            let range = rand::random::<u8>().max(1);
+            let mut partial_hash_array = [0_u8; BUCKET_LEN];
+            for p in &mut partial_hash_array {
+                *p = rand::random::<u8>() % range;
+            }
+            let partial_hash = rand::random::<u8>() % range;
+            assert_eq!(
+                match_partial_hash_sse2(&partial_hash_array, partial_hash),
+                match_partial_hash_scalar(&partial_hash_array, partial_hash),
+                "{partial_hash_array:?} {partial_hash}"
+            );
+        }
+        let partial_hash_array = [u8::MAX; BUCKET_LEN];
+        assert_eq!(
+            match_partial_hash_sse2(&partial_hash_array, u8::MAX),
+            u32::MAX
+        );
+        assert_eq!(match_partial_hash_sse2(&partial_hash_array, 0), 0);
+    }
+
+    #[test]
+    fn slot_storage_len() {
+        fn check<const LEN: usize>() {
+            type B = Bucket<usize, usize, (), SEQUENTIAL>;
+            let partial_hash = |k: usize| u8::try_from(k % 3).unwrap();
+            let mut metadata: Metadata<usize, usize, LEN> = Metadata::default();
+            let mut data_block: DataBlock<usize, usize, LEN> =
+                unsafe { MaybeUninit::uninit().assume_init() };
+            for k in 0..LEN {
+                let free_index = metadata.occupied_bitmap.trailing_ones() as usize;
+                assert_eq!(free_index, k);
+                B::insert_entry_with(
+                    &mut metadata,
+                    &mut data_block,
+                    free_index,
+                    partial_hash(k),
+                    || (k, k * 2),
+                );
+            }
+
+            // Every slot is occupied, therefore the next entry has to go to a `LinkedBucket`.
+            assert_eq!(metadata.occupied_bitmap.trailing_ones() as usize, LEN);
+            for k in 0..LEN {
+                let (entry, index) =
+                    B::search_data_block(&metadata, &data_block, &k, partial_hash(k)).unwrap();
+                assert_eq!(entry, &(k, k * 2));
+                assert_eq!(index, k);
+            }
+            assert!(
+                B::search_data_block(&metadata, &data_block, &LEN, partial_hash(LEN)).is_none()
+            );
+
+            // A vacated slot is reused by the next insertion.
+            let k = LEN / 2;
+            metadata.occupied_bitmap &= !(1_u32 << k);
+            assert_eq!(metadata.occupied_bitmap.trailing_ones() as usize, k);
+            assert!(B::search_data_block(&metadata, &data_block, &k, partial_hash(k)).is_none());
+        }
+
+        check::<LINKED_BUCKET_LEN>();
+        check::<BUCKET_LEN>();
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn deep_overflow_lock_bits() {
+        let num_entries = 1024;
+        let mut data_block: DataBlock<usize, usize, BUCKET_LEN> =
+            unsafe { MaybeUninit::uninit().assume_init() };
+        let mut bucket: Bucket<usize, usize, (), SEQUENTIAL> = default_bucket();
+        let guard = Guard::new();
+        let mut locker = Locker::lock(&mut bucket, &guard).unwrap();
+        for k in 0..num_entries {
+            locker.insert_with(&mut data_block, 0, || (k, k), &guard);
+            assert_eq!(locker.state.load(Relaxed), LOCK);
+        }
+        assert_eq!(locker.num_entries(), num_entries);
+        assert_eq!(locker.actual_occupancy(&guard), num_entries);
+        assert_eq!(
+            locker.num_linked_buckets(&guard),
+            (num_entries - BUCKET_LEN) / LINKED_BUCKET_LEN
+        );
+        drop(locker);
+        assert_eq!(bucket.state.load(Relaxed), 0);
+
+        let reader = Reader::lock(&bucket, &guard).unwrap();
+        assert_eq!(reader.state.load(Relaxed) & LOCK_MASK, 1);
+        for k in 0..num_entries {
+            assert!(reader.get_entry_ptr(&data_block, &k, 0, &guard).is_valid());
+        }
+        drop(reader);
+
+        let mut locker = Locker::lock(&mut bucket, &guard).unwrap();
+        for k in 0..num_entries {
+            let mut entry_ptr = locker.get_entry_ptr(&data_block, &k, 0, &guard);
+            assert_eq!(
+                locker.remove(&mut data_block, &mut entry_ptr, &guard),
+                (k, k)
+            );
+            assert_eq!(locker.state.load(Relaxed), LOCK);
+        }
+        assert_eq!(locker.num_entries(), 0);
+        assert_eq!(locker.num_linked_buckets(&guard), 0);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn remove_middle_slot() {
+        let mut data_block: DataBlock<usize, usize, BUCKET_LEN> =
+            unsafe { MaybeUninit::uninit().assume_init() };
+        let mut bucket: Bucket<usize, usize, (), SEQUENTIAL> = default_bucket();
+        let guard = Guard::new();
+        let mut locker = Locker::lock(&mut bucket, &guard).unwrap();
+
+        for k in 0..8_u8 {
+            let k_usize = usize::from(k);
+            locker.insert_with(&mut data_block, k, || (k_usize, k_usize), &guard);
+        }
+        assert_eq!(locker.metadata.occupied_bitmap, 0b1111_1111);
+
+        let mut entry_ptr = locker.get_entry_ptr(&data_block, &3, 3, &guard);
+        assert_eq!(
+            locker.remove(&mut data_block, &mut entry_ptr, &guard),
+            (3, 3)
+        );
+        assert_eq!(locker.metadata.occupied_bitmap, 0b1111_0111);
+        for k in (0..8_u8).filter(|k| *k != 3) {
+            let k_usize = usize::from(k);
+            assert_eq!(
+                locker.search_entry(&data_block, &k_usize, k, &guard),
+                Some(&(k_usize, k_usize))
+            );
+        }
+
+        // The vacant slot is reused by the next insertion.
+        let entry_ptr = locker.insert_with(&mut data_block, 11, || (11, 11), &guard);
+        assert_eq!(entry_ptr.partial_hash(&*locker), 11);
+        assert_eq!(locker.metadata.occupied_bitmap, 0b1111_1111);
+        assert_eq!(locker.metadata.partial_hash_array[3], 11);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn remove_relink() {
+        let mut data_block: DataBlock<usize, String, BUCKET_LEN> =
+            unsafe { MaybeUninit::uninit().assume_init() };
+        let mut bucket: Bucket<usize, String, (), SEQUENTIAL> = default_bucket();
+        let guard = Guard::new();
+        let mut locker = Locker::lock(&mut bucket, &guard).unwrap();
+
+        // Creates three linked buckets; the most recently created one is at the head.
+        let num_entries = BUCKET_LEN + LINKED_BUCKET_LEN * 3;
+        for k in 0..num_entries {
+            locker.insert_with(&mut data_block, 0, || (k, k.to_string()), &guard);
+        }
+        let head_ptr = locker.metadata.link.load(Acquire, &guard);
+        let middle_ptr = head_ptr
+            .as_ref()
+            .unwrap()
+            .metadata
+            .link
+            .load(Acquire, &guard);
+        let tail_ptr = middle_ptr
+            .as_ref()
+            .unwrap()
+            .metadata
+            .link
+            .load(Acquire, &guard);
+        assert!(tail_ptr.as_ref().unwrap().metadata.link.is_null(Relaxed));
+
+        let mut remove = |locker: &mut Locker<usize, String, (), SEQUENTIAL>, k: usize| {
+            let mut entry_ptr = locker.get_entry_ptr(&data_block, &k, 0, &guard);
+            assert!(entry_ptr.is_valid());
+            assert_eq!(
+                locker.remove(&mut data_block, &mut entry_ptr, &guard),
+                (k, k.to_string())
+            );
+            assert!(locker.search_entry(&data_block, &k, 0, &guard).is_none());
+        };
+
+        // Remove the middle linked bucket.
+        for k in BUCKET_LEN + LINKED_BUCKET_LEN..BUCKET_LEN + LINKED_BUCKET_LEN * 2 {
+            remove(&mut locker, k);
+        }
+        assert_eq!(locker.metadata.link.load(Acquire, &guard), head_ptr);
+        assert_eq!(
+            head_ptr
+                .as_ref()
+                .unwrap()
+                .metadata
+                .link
+                .load(Acquire, &guard),
+            tail_ptr
+        );
+        assert_eq!(
+            tail_ptr
+                .as_ref()
+                .unwrap()
+                .prev_link
+                .load(Relaxed)
+                .cast_const(),
+            head_ptr.as_ptr()
+        );
+
+        // Remove the head linked bucket.
+        for k in BUCKET_LEN + LINKED_BUCKET_LEN * 2..num_entries {
+            remove(&mut locker, k);
+        }
+        assert_eq!(locker.metadata.link.load(Acquire, &guard), tail_ptr);
+        assert!(tail_ptr.as_ref().unwrap().prev_link.load(Relaxed).is_null());
+
+        // Remove the last linked bucket.
+        for k in BUCKET_LEN..BUCKET_LEN + LINKED_BUCKET_LEN {
+            remove(&mut locker, k);
+        }
+        assert!(locker.metadata.link.is_null(Relaxed));
+        assert_eq!(locker.num_entries(), BUCKET_LEN);
+
+        // The freed slot is reused.
+        remove(&mut locker, 5);
+        assert_eq!(locker.metadata.occupied_bitmap, !(1_u32 << 5));
+        let entry_ptr = locker.insert_with(&mut data_block, 0, || (5, 5.to_string()), &guard);
+        assert_eq!(entry_ptr.current_index, 5);
+        assert!(entry_ptr.current_link_ptr.is_null());
+        assert!(locker.metadata.link.is_null(Relaxed));
+        assert_eq!(locker.num_entries(), BUCKET_LEN);
+
+        locker.drop_entries(&mut data_block);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn iterate_entries() {
+        fn collect<L: LruList, const TYPE: char>(
+            bucket: &Bucket<usize, usize, L, TYPE>,
+            data_block: &DataBlock<usize, usize, BUCKET_LEN>,
+            guard: &Guard,
+        ) -> Vec<usize> {
+            let mut keys = Vec::new();
+            let mut entry_ptr = EntryPtr::new(guard);
+            while entry_ptr.move_to_next(bucket, guard) {
+                keys.push(entry_ptr.get(data_block).0);
+            }
+            assert!(!entry_ptr.move_to_next(bucket, guard));
+            keys.sort_unstable();
+            keys
+        }
+
+        let mut data_block: DataBlock<usize, usize, BUCKET_LEN> =
+            unsafe { MaybeUninit::uninit().assume_init() };
+        let mut bucket: Bucket<usize, usize, (), SEQUENTIAL> = default_bucket();
+        let guard = Guard::new();
+        let mut locker = Locker::lock(&mut bucket, &guard).unwrap();
+        assert!(collect(&locker, &data_block, &guard).is_empty());
+
+        let num_entries = BUCKET_LEN + LINKED_BUCKET_LEN * 2 + 3;
+        for k in 0..num_entries {
+            locker.insert_with(&mut data_block, 0, || (k, k), &guard);
+            assert_eq!(
+                collect(&locker, &data_block, &guard),
+                (0..=k).collect::<Vec<_>>()
+            );
+        }
+
+        // Only linked buckets contain entries.
+        for k in 0..BUCKET_LEN {
+            let mut entry_ptr = locker.get_entry_ptr(&data_block, &k, 0, &guard);
+            locker.remove(&mut data_block, &mut entry_ptr, &guard);
+        }
+        assert_eq!(locker.metadata.occupied_bitmap, 0);
+        assert_eq!(
+            collect(&locker, &data_block, &guard),
+            (BUCKET_LEN..num_entries).collect::<Vec<_>>()
+        );
+
+        locker.drop_entries(&mut data_block);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn drop_long_chain() {
+        fn build_and_drop<const TYPE: char>() {
+            let mut data_block: DataBlock<usize, String, BUCKET_LEN> =
+                unsafe { MaybeUninit::uninit().assume_init() };
+            let mut bucket: Bucket<usize, String, (), TYPE> = default_bucket();
+            let guard = Guard::new();
+            let mut locker = Locker::lock(&mut bucket, &guard).unwrap();
+            for k in 0..BUCKET_LEN + LINKED_BUCKET_LEN * 4096 {
+                locker.insert_with(&mut data_block, 0, || (k, k.to_string()), &guard);
+            }
+            locker.drop_entries(&mut data_block);
+            assert!(locker.metadata.link.is_null(Relaxed));
+        }
+
+        // Linked buckets must be dropped without recursion.
+        let thread_handle = std::thread::Builder::new()
+            .stack_size(64 * 1024)
+            .spawn(|| {
+                build_and_drop::<SEQUENTIAL>();
+                build_and_drop::<OPTIMISTIC>();
+            })
+            .unwrap();
+        assert!(thread_handle.join().is_ok());
+    }
 }
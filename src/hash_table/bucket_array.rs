@@ -156,6 +156,10 @@ impl<K, V, L: LruList, const TYPE: char> BucketArray<K, V, L, TYPE> {
     }
 
     /// Returns the partial hash value of the given hash.
+    ///
+    /// The partial hash value is taken from the lower bits of the hash, whereas the upper bits are
+    /// used to calculate the [`Bucket`] index. Any value, including `0`, is a valid partial hash
+    /// since vacant slots are tracked by a separate bitmap.
     #[allow(clippy::cast_possible_truncation)]
     #[inline]
     pub(crate) const fn partial_hash(hash: u64) -> u8 {
@@ -323,7 +327,45 @@ mod test {
                 array.num_buckets()
             );
             assert!(array.num_entries() >= s, "{s} {}", array.num_entries());
+            for i in 0..array.num_buckets() {
+                assert_eq!(
+                    (array.bucket(i) as *const Bucket<_, _, _, _>) as usize % 64,
+                    0
+                );
+            }
             array.num_cleared_buckets.store(array.array_len, Relaxed);
         }
     }
+
+    #[test]
+    fn hash_distribution() {
+        use std::collections::hash_map::RandomState;
+        use std::hash::{BuildHasher, Hash, Hasher};
+
+        let build_hasher = RandomState::new();
+        let array: BucketArray<usize, usize, (), OPTIMISTIC> =
+            BucketArray::new(BUCKET_LEN * 64, AtomicShared::default());
+        let num_keys = 1_usize << 16;
+        let mut partial_hash_counts = [0_usize; 256];
+        let mut bucket_counts = vec![0_usize; array.num_buckets()];
+        for k in 0..num_keys {
+            let mut hasher = build_hasher.build_hasher();
+            k.hash(&mut hasher);
+            let hash = hasher.finish();
+            partial_hash_counts
+                [BucketArray::<usize, usize, (), OPTIMISTIC>::partial_hash(hash) as usize] += 1;
+            let index = array.calculate_bucket_index(hash);
+            assert!(index < array.num_buckets());
+            bucket_counts[index] += 1;
+        }
+
+        // Sequential keys are evenly distributed: no value gets more than twice its fair share.
+        assert!(partial_hash_counts
+            .iter()
+            .all(|c| *c > 0 && *c < num_keys / 256 * 2));
+        assert!(bucket_counts
+            .iter()
+            .all(|c| *c > 0 && *c < num_keys / array.num_buckets() * 2));
+        array.num_cleared_buckets.store(array.array_len, Relaxed);
+    }
 }
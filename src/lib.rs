@@ -40,6 +40,9 @@ mod maybe_std {
     pub(crate) use std::thread::yield_now;
 }
 
+#[cfg(feature = "metrics")]
+pub mod metrics;
+
 pub mod queue;
 pub use queue::Queue;
 
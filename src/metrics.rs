@@ -0,0 +1,81 @@
+//! Lock contention metrics, enabled by the `metrics` feature.
+//!
+//! A user-provided hook is invoked on every [`LockEvent`]. The hook is called while the lock is
+//! being acquired, therefore it should be as cheap as incrementing a counter.
+
+use std::ptr::null_mut;
+use std::sync::atomic::AtomicPtr;
+use std::sync::atomic::Ordering::{Acquire, Release};
+
+/// [`LockEvent`] describes the outcome of an attempt to lock a bucket.
+///
+/// Every successful acquisition of a bucket lock reports [`LockEvent::Contended`] zero or more
+/// times followed by [`LockEvent::Acquired`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LockEvent {
+    /// The lock was acquired.
+    Acquired,
+
+    /// The lock could not be acquired immediately, and the thread or task was put in the wait
+    /// queue.
+    Contended,
+}
+
+/// The hook, or `null` if none is set.
+static HOOK: AtomicPtr<()> = AtomicPtr::new(null_mut());
+
+/// Sets the hook invoked on every [`LockEvent`].
+///
+/// The hook replaces the previous one, and applies to all the containers in the process.
+///
+/// # Examples
+///
+/// ```
+/// use scc::metrics::{self, LockEvent};
+/// use scc::HashMap;
+/// use std::sync::atomic::AtomicUsize;
+/// use std::sync::atomic::Ordering::Relaxed;
+///
+/// static CONTENDED: AtomicUsize = AtomicUsize::new(0);
+///
+/// metrics::set_hook(|event| {
+///     if event == LockEvent::Contended {
+///         CONTENDED.fetch_add(1, Relaxed);
+///     }
+/// });
+///
+/// let hashmap: HashMap<u64, u32> = HashMap::default();
+/// assert!(hashmap.insert(1, 0).is_ok());
+///
+/// metrics::clear_hook();
+/// ```
+#[inline]
+pub fn set_hook(hook: fn(LockEvent)) {
+    HOOK.store(hook as *mut (), Release);
+}
+
+/// Clears the hook.
+///
+/// # Examples
+///
+/// ```
+/// use scc::metrics;
+///
+/// metrics::set_hook(|_| ());
+/// metrics::clear_hook();
+/// ```
+#[inline]
+pub fn clear_hook() {
+    HOOK.store(null_mut(), Release);
+}
+
+/// Reports a [`LockEvent`] to the hook.
+#[inline]
+pub(crate) fn record(event: LockEvent) {
+    let hook = HOOK.load(Acquire);
+    if !hook.is_null() {
+        // Safety: `HOOK` only ever stores `null` or a `fn(LockEvent)`.
+        let hook = unsafe { std::mem::transmute::<*mut (), fn(LockEvent)>(hook) };
+        hook(event);
+    }
+}
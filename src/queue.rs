@@ -2,17 +2,51 @@
 
 use super::ebr::{AtomicShared, Guard, Ptr, Shared, Tag};
 use super::linked_list::{Entry, LinkedList};
+use super::wait_queue::WaitQueue;
 use std::fmt::{self, Debug};
 use std::iter::FusedIterator;
-use std::sync::atomic::Ordering::{AcqRel, Acquire, Relaxed};
+use std::sync::atomic::Ordering::{AcqRel, Acquire, Relaxed, SeqCst};
+use std::sync::atomic::{fence, AtomicUsize};
 
 /// [`Queue`] is a lock-free concurrent first-in-first-out container.
+///
+/// A [`Queue`] created by [`Queue::with_capacity`] is bounded: pushing an entry into a full
+/// [`Queue`] blocks the current thread until another thread pops an entry, unless it is done
+/// through a fallible method such as [`Queue::try_push`].
 pub struct Queue<T> {
     /// `oldest` points to the oldest entry in the [`Queue`].
     oldest: AtomicShared<Entry<T>>,
 
     /// `newest` *eventually* points to the newest entry in the [`Queue`].
     newest: AtomicShared<Entry<T>>,
+
+    /// `room` limits the number of entries if the [`Queue`] is bounded.
+    room: Option<Room>,
+
+    /// `not_empty` holds threads waiting for an entry to be pushed.
+    not_empty: Waiters,
+}
+
+/// [`Room`] keeps track of the number of entries in a bounded [`Queue`].
+struct Room {
+    /// The maximum number of entries.
+    capacity: usize,
+
+    /// The number of entries that are pushed and not yet popped.
+    num_entries: AtomicUsize,
+
+    /// `not_full` holds threads waiting for an entry to be popped.
+    not_full: Waiters,
+}
+
+/// [`Waiters`] holds threads waiting for a [`Queue`] to change.
+#[derive(Default)]
+struct Waiters {
+    /// The number of threads that are waiting or about to wait.
+    num_waiters: AtomicUsize,
+
+    /// `wait_queue` holds the waiting threads.
+    wait_queue: WaitQueue,
 }
 
 /// An iterator over the entries of a [`Queue`].
@@ -26,7 +60,8 @@ pub struct Iter<'g, T> {
 impl<T: 'static> Queue<T> {
     /// Pushes an instance of `T`.
     ///
-    /// Returns a [`Shared`] holding a strong reference to the newly pushed entry.
+    /// Returns a [`Shared`] holding a strong reference to the newly pushed entry. If the
+    /// [`Queue`] is bounded and full, it blocks the current thread until an entry is popped.
     ///
     /// # Examples
     ///
@@ -39,6 +74,7 @@ impl<T: 'static> Queue<T> {
     /// ```
     #[inline]
     pub fn push(&self, val: T) -> Shared<Entry<T>> {
+        self.reserve_blocking();
         match self.push_if_internal(val, |_| true, &Guard::new()) {
             Ok(entry) => entry,
             Err(_) => {
@@ -47,11 +83,38 @@ impl<T: 'static> Queue<T> {
         }
     }
 
+    /// Pushes an instance of `T` if the [`Queue`] is not full.
+    ///
+    /// Returns a [`Shared`] holding a strong reference to the newly pushed entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error along with the supplied instance if the [`Queue`] is bounded and full.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::Queue;
+    ///
+    /// let queue: Queue<usize> = Queue::with_capacity(1);
+    ///
+    /// assert!(queue.try_push(11).is_ok());
+    /// assert_eq!(queue.try_push(17).err(), Some(17));
+    ///
+    /// assert_eq!(queue.pop().map(|e| **e), Some(11));
+    /// assert!(queue.try_push(17).is_ok());
+    /// ```
+    #[inline]
+    pub fn try_push(&self, val: T) -> Result<Shared<Entry<T>>, T> {
+        self.push_if(val, |_| true)
+    }
+
     /// Pushes an instance of `T` if the newest entry satisfies the given condition.
     ///
     /// # Errors
     ///
-    /// Returns an error along with the supplied instance if the condition is not met.
+    /// Returns an error along with the supplied instance if the condition is not met or the
+    /// [`Queue`] is bounded and full.
     ///
     /// # Examples
     ///
@@ -71,7 +134,14 @@ impl<T: 'static> Queue<T> {
         val: T,
         cond: F,
     ) -> Result<Shared<Entry<T>>, T> {
+        if !self.try_reserve() {
+            return Err(val);
+        }
         self.push_if_internal(val, cond, &Guard::new())
+            .map_err(|val| {
+                self.release();
+                val
+            })
     }
 
     /// Returns a guarded reference to the oldest entry.
@@ -109,6 +179,38 @@ impl<T: 'static> Queue<T> {
 }
 
 impl<T> Queue<T> {
+    /// Creates an empty bounded [`Queue`] that can hold up to `capacity` entries.
+    ///
+    /// A `capacity` of `0` is treated as `1`, and `usize::MAX` makes the [`Queue`] unbounded.
+    /// Room is only given back when an entry is popped, therefore deleting an entry through
+    /// [`LinkedList::delete_self`] does not make room for another entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::Queue;
+    ///
+    /// let queue: Queue<usize> = Queue::with_capacity(2);
+    ///
+    /// queue.push(1);
+    /// queue.push(2);
+    /// assert!(queue.try_push(3).is_err());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            oldest: AtomicShared::default(),
+            newest: AtomicShared::default(),
+            room: (capacity != usize::MAX).then(|| Room {
+                capacity: capacity.max(1),
+                num_entries: AtomicUsize::new(0),
+                not_full: Waiters::default(),
+            }),
+            not_empty: Waiters::default(),
+        }
+    }
+
     /// Pushes an instance of `T` without checking the lifetime of `T`.
     ///
     /// Returns a [`Shared`] holding a strong reference to the newly pushed entry.
@@ -130,6 +232,7 @@ impl<T> Queue<T> {
     /// ```
     #[inline]
     pub unsafe fn push_unchecked(&self, val: T) -> Shared<Entry<T>> {
+        self.reserve_blocking();
         match self.push_if_internal(val, |_| true, &Guard::new()) {
             Ok(entry) => entry,
             Err(_) => {
@@ -166,7 +269,14 @@ impl<T> Queue<T> {
         val: T,
         cond: F,
     ) -> Result<Shared<Entry<T>>, T> {
+        if !self.try_reserve() {
+            return Err(val);
+        }
         self.push_if_internal(val, cond, &Guard::new())
+            .map_err(|val| {
+                self.release();
+                val
+            })
     }
 
     /// Pops the oldest entry.
@@ -197,6 +307,34 @@ impl<T> Queue<T> {
         }
     }
 
+    /// Pops the oldest entry, blocking the current thread until an entry is pushed if the
+    /// [`Queue`] is empty.
+    ///
+    /// One of the waiting threads is woken up whenever an entry is pushed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scc::Queue;
+    /// use std::sync::Arc;
+    /// use std::thread;
+    ///
+    /// let queue: Arc<Queue<usize>> = Arc::default();
+    ///
+    /// let queue_clone = queue.clone();
+    /// let consumer = thread::spawn(move || **queue_clone.pop_blocking());
+    ///
+    /// queue.push(7);
+    /// assert_eq!(consumer.join().unwrap(), 7);
+    /// ```
+    #[inline]
+    pub fn pop_blocking(&self) -> Shared<Entry<T>> {
+        if let Some(entry) = self.pop() {
+            return entry;
+        }
+        self.not_empty.wait(|| self.pop())
+    }
+
     /// Pops the oldest entry if the entry satisfies the given condition.
     ///
     /// Returns `None` if the [`Queue`] is empty.
@@ -232,6 +370,7 @@ impl<T> Queue<T> {
                     return Err(oldest_entry);
                 }
                 if oldest_entry.delete_self(Relaxed) {
+                    self.release();
                     self.cleanup_oldest(&guard);
                     return Ok(Some(oldest_entry));
                 }
@@ -366,11 +505,12 @@ impl<T> Queue<T> {
 
         let mut new_entry = unsafe { Shared::new_unchecked(Entry::new(val)) };
         loop {
+            // The entry is linked with `SeqCst` for `Waiters::signal` to observe waiting threads.
             let result = if let Some(newest_entry) = newest_ptr.as_ref() {
                 newest_entry.next().compare_exchange(
                     Ptr::null(),
                     (Some(new_entry.clone()), Tag::None),
-                    AcqRel,
+                    SeqCst,
                     Acquire,
                     guard,
                 )
@@ -378,7 +518,7 @@ impl<T> Queue<T> {
                 self.oldest.compare_exchange(
                     newest_ptr,
                     (Some(new_entry.clone()), Tag::None),
-                    AcqRel,
+                    SeqCst,
                     Acquire,
                     guard,
                 )
@@ -391,6 +531,8 @@ impl<T> Queue<T> {
                         // The `Queue` was emptied in the meantime.
                         self.newest.swap((None, Tag::None), Acquire);
                     }
+
+                    self.not_empty.signal();
                     return Ok(new_entry);
                 }
                 Err((_, actual_ptr)) => {
@@ -443,6 +585,26 @@ impl<T> Queue<T> {
         oldest_ptr
     }
 
+    /// Tries to reserve room for an entry.
+    fn try_reserve(&self) -> bool {
+        self.room.as_ref().map_or(true, Room::try_reserve)
+    }
+
+    /// Reserves room for an entry, blocking the current thread until an entry is popped if the
+    /// [`Queue`] is full.
+    fn reserve_blocking(&self) {
+        if let Some(room) = self.room.as_ref() {
+            room.reserve_blocking();
+        }
+    }
+
+    /// Releases the room reserved for an entry.
+    fn release(&self) {
+        if let Some(room) = self.room.as_ref() {
+            room.unreserve();
+        }
+    }
+
     /// Traverses the linked list to the end.
     fn traverse<'g>(start: Ptr<'g, Entry<T>>, guard: &'g Guard) -> Ptr<'g, Entry<T>> {
         let mut current = start;
@@ -457,15 +619,86 @@ impl<T> Queue<T> {
     }
 }
 
+impl Room {
+    /// Tries to reserve room for an entry.
+    fn try_reserve(&self) -> bool {
+        let mut current = self.num_entries.load(Relaxed);
+        while current < self.capacity {
+            match self
+                .num_entries
+                .compare_exchange_weak(current, current + 1, Relaxed, Relaxed)
+            {
+                Ok(_) => return true,
+                Err(actual) => current = actual,
+            }
+        }
+        false
+    }
+
+    /// Reserves room for an entry, blocking the current thread until an entry is popped if there
+    /// is no room left.
+    fn reserve_blocking(&self) {
+        if !self.try_reserve() {
+            self.not_full.wait(|| self.try_reserve().then_some(()));
+        }
+    }
+
+    /// Releases the room reserved for an entry.
+    fn unreserve(&self) {
+        // `SeqCst` for `Waiters::signal`.
+        self.num_entries.fetch_sub(1, SeqCst);
+        self.not_full.signal();
+    }
+}
+
+impl Waiters {
+    /// Blocks the current thread until `f` returns `Some`.
+    fn wait<R, F: FnMut() -> Option<R>>(&self, mut f: F) -> R {
+        self.num_waiters.fetch_add(1, SeqCst);
+        let result = loop {
+            if let Ok(result) = self.wait_queue.wait_sync(|| {
+                // Either `f` observes the change, or `signal` observes the wait queue entry.
+                fence(SeqCst);
+                f().ok_or(())
+            }) {
+                break result;
+            }
+            if let Some(result) = f() {
+                break result;
+            }
+        };
+        self.num_waiters.fetch_sub(1, Relaxed);
+        result
+    }
+
+    /// Wakes up one of the waiting threads.
+    ///
+    /// The change that the waiting threads are waiting for has to be made with `SeqCst` before the
+    /// call, so that the fence is only needed when there is a waiting thread.
+    fn signal(&self) {
+        if self.num_waiters.load(SeqCst) != 0 {
+            fence(SeqCst);
+            self.wait_queue.signal_one();
+        }
+    }
+}
+
 impl<T: Clone> Clone for Queue<T> {
     #[inline]
     fn clone(&self) -> Self {
-        let self_clone = Self::default();
+        let self_clone =
+            Self::with_capacity(self.room.as_ref().map_or(usize::MAX, |room| room.capacity));
         let guard = Guard::new();
         let mut current = self.oldest.load(Acquire, &guard);
         while let Some(entry) = current.as_ref() {
             let next = entry.next_ptr(Acquire, &guard);
-            let _result = self_clone.push_if_internal((**entry).clone(), |_| true, &guard);
+            if self_clone.try_reserve()
+                && self_clone
+                    .push_if_internal((**entry).clone(), |_| true, &guard)
+                    .is_err()
+            {
+                self_clone.release();
+            }
             current = next;
         }
         self_clone
@@ -490,10 +723,7 @@ impl<T: Debug> Debug for Queue<T> {
 impl<T> Default for Queue<T> {
     #[inline]
     fn default() -> Self {
-        Self {
-            oldest: AtomicShared::default(),
-            newest: AtomicShared::default(),
-        }
+        Self::with_capacity(usize::MAX)
     }
 }
 
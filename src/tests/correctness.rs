@@ -1,13 +1,30 @@
+/// `Collide(key, n)` takes one of `n` hash values in order for entries to be stored in the same
+/// buckets.
+#[cfg(not(feature = "loom"))]
+#[cfg(test)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Collide(usize, usize);
+
+#[cfg(not(feature = "loom"))]
+#[cfg(test)]
+impl std::hash::Hash for Collide {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        (self.0 % self.1).hash(state);
+    }
+}
+
 #[cfg(not(feature = "loom"))]
 #[cfg(test)]
 mod hashmap_test {
-    use crate::hash_map::{self, Entry, Reserve};
+    use super::Collide;
+    use crate::hash_map::{self, Entry, HashMapBuilder, Reserve};
     use crate::{Equivalent, HashMap};
     use proptest::prelude::*;
     use proptest::strategy::ValueTree;
     use proptest::test_runner::TestRunner;
+    use std::collections::hash_map::{DefaultHasher, RandomState};
     use std::collections::BTreeSet;
-    use std::hash::{Hash, Hasher};
+    use std::hash::{BuildHasher, Hash, Hasher};
     use std::panic::UnwindSafe;
     use std::rc::Rc;
     use std::sync::atomic::Ordering::{Acquire, Relaxed, Release};
@@ -96,7 +113,7 @@ mod hashmap_test {
 
     impl Hash for EqTest {
         fn hash<H: Hasher>(&self, state: &mut H) {
-            self.0.hash(state)
+            self.0.hash(state);
         }
     }
 
@@ -156,6 +173,63 @@ mod hashmap_test {
         }
     }
 
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn clear_concurrent() {
+        struct D(usize, Arc<Vec<AtomicUsize>>);
+        impl Drop for D {
+            fn drop(&mut self) {
+                self.1[self.0].fetch_add(1, Relaxed);
+            }
+        }
+
+        let workload_size = 1_usize << 14;
+        let num_dropped: Arc<Vec<AtomicUsize>> =
+            Arc::new((0..workload_size).map(|_| AtomicUsize::new(0)).collect());
+        let hashmap: Arc<HashMap<usize, D>> = Arc::new(HashMap::default());
+        let hashmap_clone = hashmap.clone();
+        let num_dropped_clone = num_dropped.clone();
+        let inserter = thread::spawn(move || {
+            for k in 0..workload_size {
+                assert!(hashmap_clone
+                    .insert(k, D(k, num_dropped_clone.clone()))
+                    .is_ok());
+            }
+        });
+        while !inserter.is_finished() {
+            hashmap.clear();
+        }
+        assert!(inserter.join().is_ok());
+
+        hashmap.clear();
+        assert_eq!(hashmap.len(), 0);
+        assert!(num_dropped.iter().all(|d| d.load(Relaxed) == 1));
+    }
+
+    #[test]
+    fn retain_even() {
+        static INST_CNT: AtomicUsize = AtomicUsize::new(0);
+
+        let hashmap: HashMap<usize, R> = HashMap::default();
+        let workload_size = 1_usize << 10;
+        for k in 0..workload_size {
+            assert!(hashmap.insert(k, R::new(&INST_CNT)).is_ok());
+        }
+        hashmap.retain(|k, v| {
+            assert!(std::ptr::eq(v.0, &INST_CNT));
+            k % 2 == 0
+        });
+
+        // Removed values were dropped.
+        assert_eq!(INST_CNT.load(Relaxed), workload_size / 2);
+        assert_eq!(hashmap.len(), workload_size / 2);
+        let mut retained = BTreeSet::new();
+        hashmap.scan(|k, _| {
+            assert!(retained.insert(*k));
+        });
+        assert!(retained.into_iter().eq((0..workload_size).step_by(2)));
+    }
+
     #[cfg_attr(miri, ignore)]
     #[test]
     fn read_remove() {
@@ -167,22 +241,22 @@ mod hashmap_test {
         let hashmap_clone = hashmap.clone();
         let barrier_clone = barrier.clone();
         let task = thread::spawn(move || {
-            hashmap_clone.read("first".into(), |_key, value| {
+            hashmap_clone.read("first", |_key, value| {
                 {
-                    let first_item = value.get(0);
+                    let first_item = value.first();
                     assert_eq!(first_item.unwrap(), &123_u8);
                 }
                 barrier_clone.wait();
                 thread::sleep(Duration::from_millis(16));
                 {
-                    let first_item = value.get(0);
+                    let first_item = value.first();
                     assert_eq!(first_item.unwrap(), &123_u8);
                 }
             });
         });
 
         barrier.wait();
-        assert!(hashmap.remove("first".into()).is_some());
+        assert!(hashmap.remove("first").is_some());
         assert!(task.join().is_ok());
     }
 
@@ -192,7 +266,6 @@ mod hashmap_test {
 
         let workload_size = 256;
         let hashmap = (0..workload_size)
-            .into_iter()
             .map(|k| (k / 2, R::new(&INST_CNT)))
             .collect::<HashMap<usize, R>>();
         assert_eq!(hashmap.len(), workload_size / 2);
@@ -236,6 +309,380 @@ mod hashmap_test {
         assert_eq!(INST_CNT.load(Relaxed), 0);
     }
 
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn debug_format() {
+        let hashmap: HashMap<usize, usize> = HashMap::default();
+        assert_eq!(format!("{hashmap:?}"), "{}");
+        assert!(hashmap.insert(1, 11).is_ok());
+        assert_eq!(format!("{hashmap:?}"), "{1: 11}");
+        assert!(hashmap.insert(2, 12).is_ok());
+        let formatted = format!("{hashmap:?}");
+        assert!(formatted == "{1: 11, 2: 12}" || formatted == "{2: 12, 1: 11}");
+
+        // Formatting a resizing `HashMap` completes.
+        let hashmap: Arc<HashMap<usize, usize>> = Arc::new(HashMap::default());
+        let done = Arc::new(AtomicUsize::new(0));
+        let hashmap_clone = hashmap.clone();
+        let done_clone = done.clone();
+        let resizer = thread::spawn(move || {
+            while done_clone.load(Acquire) == 0 {
+                for k in 0..4096 {
+                    assert!(hashmap_clone.insert(k, k).is_ok());
+                }
+                hashmap_clone.clear();
+            }
+        });
+        for _ in 0..64 {
+            let formatted = format!("{hashmap:?}");
+            assert!(formatted.starts_with('{') && formatted.ends_with('}'));
+        }
+        done.store(1, Release);
+        assert!(resizer.join().is_ok());
+    }
+
+    #[test]
+    fn contains_collision() {
+        // Every key has the same hash value.
+        // `V` does not have to be cloneable.
+        let hashmap: HashMap<Collide, std::sync::Mutex<usize>> = HashMap::default();
+        assert!(!hashmap.contains(&Collide(0, 1)));
+        for k in 0..64 {
+            assert!(hashmap
+                .insert(Collide(k * 2, 1), std::sync::Mutex::new(k))
+                .is_ok());
+        }
+        for k in 0..64 {
+            assert!(hashmap.contains(&Collide(k * 2, 1)));
+            assert!(!hashmap.contains(&Collide(k * 2 + 1, 1)));
+        }
+        assert!(hashmap.remove(&Collide(0, 1)).is_some());
+        assert!(!hashmap.contains(&Collide(0, 1)));
+        assert!(hashmap.contains(&Collide(2, 1)));
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn upsert_displaced() {
+        let checker = Arc::new(AtomicUsize::new(0));
+        let hashmap: HashMap<usize, Data> = HashMap::default();
+        assert!(hashmap.upsert(1, Data::new(1, checker.clone())).is_none());
+        let displaced = hashmap.upsert(1, Data::new(2, checker.clone())).unwrap();
+        assert_eq!(displaced.data, 1);
+        assert_eq!(checker.load(Relaxed), 2);
+        drop(displaced);
+        assert_eq!(checker.load(Relaxed), 1);
+        assert_eq!(hashmap.read(&1, |_, v| v.data), Some(2));
+        drop(hashmap);
+        assert_eq!(checker.load(Relaxed), 0);
+
+        // The key never goes missing while being upserted.
+        let num_iters = 4096;
+        let hashmap: Arc<HashMap<usize, usize>> = Arc::new(HashMap::default());
+        assert!(hashmap.upsert(0, 0).is_none());
+        let hashmap_clone = hashmap.clone();
+        let reader = thread::spawn(move || {
+            for _ in 0..num_iters {
+                assert!(hashmap_clone.contains(&0));
+            }
+        });
+        for i in 0..num_iters {
+            assert_eq!(hashmap.upsert(0, i + 1), Some(i));
+        }
+        assert!(reader.join().is_ok());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn replace_existing() {
+        let hashmap: HashMap<usize, Data> = HashMap::default();
+        let checker = Arc::new(AtomicUsize::new(0));
+        assert!(hashmap.replace(&1, Data::new(1, checker.clone())).is_none());
+        assert!(!hashmap.contains(&1));
+        assert_eq!(checker.load(Relaxed), 0);
+
+        assert!(hashmap.insert(1, Data::new(1, checker.clone())).is_ok());
+        let old = hashmap.replace(&1, Data::new(2, checker.clone())).unwrap();
+        assert_eq!(old.data, 1);
+        drop(old);
+        assert_eq!(hashmap.read(&1, |_, v| v.data), Some(2));
+        assert_eq!(hashmap.len(), 1);
+        assert_eq!(checker.load(Relaxed), 1);
+
+        drop(hashmap);
+        assert_eq!(checker.load(Relaxed), 0);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn replace_existing_async() {
+        let hashmap: HashMap<usize, usize> = HashMap::default();
+        assert!(hashmap.replace_async(&1, 1).await.is_none());
+        assert!(hashmap.is_empty());
+        assert!(hashmap.insert_async(1, 0).await.is_ok());
+        assert_eq!(hashmap.replace_async(&1, 1).await, Some(0));
+        assert_eq!(hashmap.read_async(&1, |_, v| *v).await, Some(1));
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn replace_if_cas() {
+        let hashmap: HashMap<usize, String> = HashMap::default();
+        assert_eq!(hashmap.replace_if(&0, &String::new(), "a".to_string()), Err(None));
+        assert!(hashmap.insert(0, "a".to_string()).is_ok());
+        assert_eq!(
+            hashmap.replace_if(&0, &"b".to_string(), "c".to_string()),
+            Err(Some("a".to_string()))
+        );
+        assert_eq!(
+            hashmap.replace_if(&0, &"a".to_string(), "b".to_string()),
+            Ok("a".to_string())
+        );
+        assert_eq!(hashmap.read(&0, |_, v| v.clone()), Some("b".to_string()));
+
+        // Concurrent increments by compare-and-swap loops are never lost.
+        let num_threads = 4;
+        let num_iters = 1024;
+        let hashmap: Arc<HashMap<usize, usize>> = Arc::new(HashMap::default());
+        assert!(hashmap.insert(0, 0).is_ok());
+        let mut thread_handles = Vec::with_capacity(num_threads);
+        for _ in 0..num_threads {
+            let hashmap_clone = hashmap.clone();
+            thread_handles.push(thread::spawn(move || {
+                let mut current = hashmap_clone.read(&0, |_, v| *v).unwrap();
+                for _ in 0..num_iters {
+                    loop {
+                        match hashmap_clone.replace_if(&0, &current, current + 1) {
+                            Ok(old) => {
+                                assert_eq!(old, current);
+                                current += 1;
+                                break;
+                            }
+                            Err(actual) => current = actual.unwrap(),
+                        }
+                    }
+                }
+            }));
+        }
+        for thread_handle in thread_handles {
+            assert!(thread_handle.join().is_ok());
+        }
+        assert_eq!(hashmap.read(&0, |_, v| *v), Some(num_threads * num_iters));
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn remove_if_stable() {
+        let hashmap: HashMap<usize, (usize, usize)> = HashMap::default();
+        assert!(hashmap.remove_if(&0, |_| unreachable!()).is_none());
+        assert!(hashmap.insert(0, (0, 0)).is_ok());
+        assert!(hashmap.remove_if(&0, |v| v.0 == 1).is_none());
+        assert_eq!(hashmap.read(&0, |_, v| *v), Some((0, 0)));
+        assert_eq!(hashmap.remove_if(&0, |v| v.0 == 0), Some((0, (0, 0))));
+        assert!(hashmap.is_empty());
+
+        // The condition observes the value updated by other threads only as a whole.
+        let num_threads = 4;
+        let num_iters = 4096;
+        let hashmap: Arc<HashMap<usize, (usize, usize)>> = Arc::new(HashMap::default());
+        let mut thread_handles = Vec::with_capacity(num_threads);
+        for thread_id in 0..num_threads {
+            let hashmap_clone = hashmap.clone();
+            thread_handles.push(thread::spawn(move || {
+                let mut num_removed = 0;
+                for i in 0..num_iters {
+                    if thread_id % 2 == 0 {
+                        let _: Result<(), _> = hashmap_clone.insert(0, (i, i));
+                        hashmap_clone.update(&0, |_, v| {
+                            v.0 += 1;
+                            v.1 += 1;
+                        });
+                    } else if hashmap_clone
+                        .remove_if(&0, |v| {
+                            assert_eq!(v.0, v.1);
+                            v.0 % 2 == 0
+                        })
+                        .is_some()
+                    {
+                        num_removed += 1;
+                    }
+                }
+                num_removed
+            }));
+        }
+        for thread_handle in thread_handles {
+            assert!(thread_handle.join().is_ok());
+        }
+        if let Some((a, b)) = hashmap.read(&0, |_, v| *v) {
+            assert_eq!(a, b);
+        }
+    }
+
+    #[test]
+    fn bucket_stats() {
+        // Every key has the same hash value in order for entries to be stored in linked buckets.
+        let hashmap: HashMap<Collide, usize> = HashMap::default();
+        assert_eq!(hashmap.bucket_stats(), hash_map::BucketStats::default());
+
+        // A bucket has `32` slots, and a linked bucket has `8` slots.
+        for k in 0..32 + 8 * 3 {
+            assert!(hashmap.insert(Collide(k, 1), k).is_ok());
+        }
+        let bucket_stats = hashmap.bucket_stats();
+        assert_eq!(bucket_stats.num_buckets(), hashmap.capacity() / 32);
+        assert_eq!(bucket_stats.num_entries(), hashmap.len());
+        assert_eq!(bucket_stats.max_linked_buckets(), 3);
+        assert_eq!(bucket_stats.num_overflowed_buckets(), 1);
+        assert!(bucket_stats.avg_entries_per_bucket() > 0.0);
+
+        for k in 0..32 + 8 * 3 {
+            assert!(hashmap.remove(&Collide(k, 1)).is_some());
+        }
+        assert_eq!(hashmap.bucket_stats().num_entries(), 0);
+    }
+
+    #[test]
+    fn overflow_rebalance() {
+        // Only the upper bits of the hash value differ, so keys are distributed in runs of
+        // consecutive keys, and buckets outside the sampling range overflow.
+        #[derive(Default)]
+        struct Skewed(u64);
+        impl Hasher for Skewed {
+            fn finish(&self) -> u64 {
+                self.0 << 55
+            }
+            fn write(&mut self, bytes: &[u8]) {
+                for b in bytes.iter().rev() {
+                    self.0 = (self.0 << 8) | u64::from(*b);
+                }
+            }
+        }
+
+        let workload_size = 512;
+        let hashmap: HashMap<usize, usize, std::hash::BuildHasherDefault<Skewed>> =
+            HashMap::default();
+        for k in 0..workload_size {
+            assert!(hashmap.insert(k, k).is_ok());
+        }
+        let bucket_stats = hashmap.bucket_stats();
+        assert_eq!(bucket_stats.num_entries(), workload_size);
+        // A bucket outside the sampling range samples the hash table by itself once it stores more
+        // than half of `BUCKET_LEN` entries in linked buckets, whereas it would end up with four
+        // linked buckets otherwise.
+        assert!(bucket_stats.num_buckets() > 8);
+        assert!(bucket_stats.max_linked_buckets() <= 2);
+        for k in 0..workload_size {
+            assert_eq!(hashmap.read(&k, |_, v| *v), Some(k));
+        }
+    }
+
+    #[test]
+    fn trait_object_values() {
+        trait Shape: Send + Sync {
+            fn area(&self) -> usize;
+        }
+        struct Square(usize, Arc<AtomicUsize>);
+        impl Shape for Square {
+            fn area(&self) -> usize {
+                self.0 * self.0
+            }
+        }
+        impl Drop for Square {
+            fn drop(&mut self) {
+                self.1.fetch_add(1, Relaxed);
+            }
+        }
+        struct Rectangle(usize, usize);
+        impl Shape for Rectangle {
+            fn area(&self) -> usize {
+                self.0 * self.1
+            }
+        }
+
+        // Every key has the same hash value in order for entries to be stored in linked buckets.
+        let num_dropped = Arc::new(AtomicUsize::new(0));
+        let hashmap: HashMap<Collide, Box<dyn Shape>> = HashMap::default();
+        for k in 0..64 {
+            let shape: Box<dyn Shape> = if k % 2 == 0 {
+                Box::new(Square(k, num_dropped.clone()))
+            } else {
+                Box::new(Rectangle(k, 2))
+            };
+            assert!(hashmap.insert(Collide(k, 1), shape).is_ok());
+        }
+        for k in 0..64 {
+            let expected = if k % 2 == 0 { k * k } else { k * 2 };
+            assert_eq!(
+                hashmap.read(&Collide(k, 1), |_, v| v.area()),
+                Some(expected)
+            );
+        }
+        assert!(hashmap.remove(&Collide(0, 1)).is_some());
+        assert_eq!(num_dropped.load(Relaxed), 1);
+        drop(hashmap);
+        assert_eq!(num_dropped.load(Relaxed), 32);
+    }
+
+    #[test]
+    fn collect_extend() {
+        let hashmap: HashMap<usize, usize> = vec![(1, 1), (2, 2), (1, 3)].into_iter().collect();
+        assert_eq!(hashmap.len(), 2);
+        assert_eq!(hashmap.read(&1, |_, v| *v), Some(3));
+        assert_eq!(hashmap.read(&2, |_, v| *v), Some(2));
+
+        let mut hashmap = hashmap;
+        hashmap.extend((2..1024).map(|k| (k, k * 2)));
+        hashmap.extend(vec![(1, 4), (1, 5)]);
+        assert_eq!(hashmap.len(), 1023);
+        assert_eq!(hashmap.read(&1, |_, v| *v), Some(5));
+        for k in 2..1024 {
+            assert_eq!(hashmap.read(&k, |_, v| *v), Some(k * 2));
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn clone_concurrent() {
+        let hashmap: Arc<HashMap<usize, usize>> = Arc::new(HashMap::default());
+        let workload_size = 1024;
+        for k in 0..workload_size {
+            assert!(hashmap.insert(k, k).is_ok());
+        }
+
+        let done = Arc::new(AtomicUsize::new(0));
+        let hashmap_clone = hashmap.clone();
+        let done_clone = done.clone();
+        let mutator = thread::spawn(move || {
+            while done_clone.load(Acquire) == 0 {
+                for k in workload_size..workload_size * 2 {
+                    assert!(hashmap_clone.insert(k, k).is_ok());
+                }
+                for k in workload_size..workload_size * 2 {
+                    assert!(hashmap_clone.remove(&k).is_some());
+                }
+            }
+        });
+
+        for _ in 0..16 {
+            let cloned = (*hashmap).clone();
+
+            // Entries that are never removed are always cloned.
+            for k in 0..workload_size {
+                assert_eq!(cloned.read(&k, |_, v| *v), Some(k));
+            }
+            cloned.scan(|k, v| assert_eq!(k, v));
+
+            // The clone is independent of the original.
+            assert!(hashmap.update(&0, |_, v| *v = usize::MAX).is_some());
+            assert_eq!(cloned.read(&0, |_, v| *v), Some(0));
+            assert!(hashmap.update(&0, |_, v| *v = 0).is_some());
+            assert!(cloned.remove(&1).is_some());
+            assert!(hashmap.contains(&1));
+        }
+        done.store(1, Release);
+        assert!(mutator.join().is_ok());
+    }
+
     #[test]
     fn compare() {
         let hashmap1: HashMap<String, usize> = HashMap::new();
@@ -254,8 +701,21 @@ mod hashmap_test {
         assert!(hashmap2.insert("Hi".to_string(), 1).is_ok());
         assert_eq!(hashmap1, hashmap2);
 
+        assert_eq!(hashmap1.replace("Hi", 3), Some(1));
+        assert_ne!(hashmap1, hashmap2);
+        assert_ne!(hashmap2, hashmap1);
+
+        assert_eq!(hashmap2.replace("Hi", 3), Some(1));
+        assert_eq!(hashmap1, hashmap2);
+
         assert!(hashmap1.remove("Hi").is_some());
         assert_ne!(hashmap1, hashmap2);
+        assert_ne!(hashmap2, hashmap1);
+
+        assert!(hashmap1.insert("Hey".to_string(), 3).is_ok());
+        assert_eq!(hashmap1.len(), hashmap2.len());
+        assert_ne!(hashmap1, hashmap2);
+        assert_ne!(hashmap2, hashmap1);
     }
 
     #[test]
@@ -267,7 +727,7 @@ mod hashmap_test {
                 L(cnt)
             }
         }
-        impl<'a> Drop for L<'a> {
+        impl Drop for L<'_> {
             fn drop(&mut self) {
                 self.0.fetch_sub(1, Relaxed);
             }
@@ -394,11 +854,1089 @@ mod hashmap_test {
             }));
         }
 
-        for r in futures::future::join_all(task_handles).await {
-            assert!(r.is_ok());
+        for r in futures::future::join_all(task_handles).await {
+            assert!(r.is_ok());
+        }
+
+        assert_eq!(hashmap.len(), 0);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn resize_sync() {
+        let hashmap: Arc<HashMap<usize, usize>> = Arc::new(HashMap::default());
+        let num_threads = 8;
+        let workload_size = 4096;
+        let barrier = Arc::new(Barrier::new(num_threads));
+        let mut thread_handles = Vec::with_capacity(num_threads);
+        for thread_id in 0..num_threads {
+            let barrier_clone = barrier.clone();
+            let hashmap_clone = hashmap.clone();
+            thread_handles.push(thread::spawn(move || {
+                barrier_clone.wait();
+                let range = (thread_id * workload_size)..((thread_id + 1) * workload_size);
+                for id in range.clone() {
+                    assert!(hashmap_clone.insert(id, id).is_ok());
+                    assert_eq!(hashmap_clone.read(&id, |_, v| *v), Some(id));
+                }
+                for id in range {
+                    assert_eq!(hashmap_clone.read(&id, |_, v| *v), Some(id));
+                }
+            }));
+        }
+        for t in thread_handles {
+            assert!(t.join().is_ok());
+        }
+
+        // The capacity has grown, and no entries were lost while resizing.
+        assert_eq!(hashmap.len(), num_threads * workload_size);
+        assert!(hashmap.capacity() >= num_threads * workload_size);
+        for id in 0..num_threads * workload_size {
+            assert_eq!(hashmap.read(&id, |_, v| *v), Some(id));
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn resize_mixed() {
+        let hashmap: Arc<HashMap<usize, usize>> = Arc::new(HashMap::default());
+        let num_threads = 4;
+        let workload_size = 1024;
+        let filler_base = num_threads * workload_size;
+        let done = Arc::new(AtomicUsize::new(0));
+
+        // The filler thread keeps the table growing and shrinking.
+        let hashmap_clone = hashmap.clone();
+        let done_clone = done.clone();
+        let filler = thread::spawn(move || {
+            let mut capacities = BTreeSet::new();
+            while done_clone.load(Acquire) != num_threads {
+                for id in filler_base..filler_base + workload_size * 16 {
+                    assert!(hashmap_clone.insert(id, id).is_ok());
+                }
+                capacities.insert(hashmap_clone.capacity());
+                for id in filler_base..filler_base + workload_size * 16 {
+                    assert_eq!(hashmap_clone.remove(&id), Some((id, id)));
+                }
+                capacities.insert(hashmap_clone.capacity());
+            }
+            capacities.len()
+        });
+
+        let mut thread_handles = Vec::with_capacity(num_threads);
+        for thread_id in 0..num_threads {
+            let hashmap_clone = hashmap.clone();
+            let done_clone = done.clone();
+            thread_handles.push(thread::spawn(move || {
+                let range = (thread_id * workload_size)..((thread_id + 1) * workload_size);
+                for _ in 0..4 {
+                    for id in range.clone() {
+                        assert!(hashmap_clone.insert(id, id).is_ok());
+                        assert!(hashmap_clone.insert(id, id + 1).is_err());
+                        assert_eq!(hashmap_clone.read(&id, |_, v| *v), Some(id));
+                    }
+                    for id in range.clone() {
+                        assert_eq!(hashmap_clone.update(&id, |_, v| *v + 1), Some(id + 1));
+                        assert_eq!(hashmap_clone.read(&id, |_, v| *v), Some(id));
+                        assert_eq!(hashmap_clone.remove(&id), Some((id, id)));
+                        assert!(hashmap_clone.read(&id, |_, v| *v).is_none());
+                    }
+                }
+                done_clone.fetch_add(1, Release);
+            }));
+        }
+        for t in thread_handles {
+            assert!(t.join().is_ok());
+        }
+        let num_capacities = filler.join().unwrap();
+        assert!(num_capacities >= 2);
+        assert_eq!(hashmap.len(), 0);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn entry_or_insert_with_once() {
+        let hashmap: Arc<HashMap<usize, usize>> = Arc::new(HashMap::default());
+        let num_threads = 8;
+        let num_keys = 256;
+        let num_calls = Arc::new(AtomicUsize::new(0));
+        let barrier = Arc::new(Barrier::new(num_threads));
+        let mut thread_handles = Vec::with_capacity(num_threads);
+        for thread_id in 0..num_threads {
+            let barrier_clone = barrier.clone();
+            let hashmap_clone = hashmap.clone();
+            let num_calls_clone = num_calls.clone();
+            thread_handles.push(thread::spawn(move || {
+                barrier_clone.wait();
+                (0..num_keys)
+                    .map(|k| {
+                        *hashmap_clone
+                            .entry(k)
+                            .or_insert_with(|| {
+                                num_calls_clone.fetch_add(1, Relaxed);
+                                k * num_threads + thread_id
+                            })
+                            .get()
+                    })
+                    .collect::<Vec<_>>()
+            }));
+        }
+
+        let observed: Vec<Vec<usize>> = thread_handles
+            .into_iter()
+            .filter_map(|t| t.join().ok())
+            .collect();
+        assert_eq!(observed.len(), num_threads);
+
+        // The factory was invoked exactly once per key, and every thread saw the same value.
+        assert_eq!(num_calls.load(Relaxed), num_keys);
+        for k in 0..num_keys {
+            let v = hashmap.read(&k, |_, v| *v).unwrap();
+            assert_eq!(v / num_threads, k);
+            assert!(observed.iter().all(|o| o[k] == v));
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn entry_lock_release() {
+        let hashmap: Arc<HashMap<usize, usize>> = Arc::new(HashMap::default());
+        assert_eq!(*hashmap.entry(1).or_insert(1).get(), 1);
+        assert_eq!(*hashmap.entry(1).or_insert(2).get(), 1);
+        assert_eq!(*hashmap.entry(2).or_insert_with(|| 2).get(), 2);
+        hashmap.entry(1).and_modify(|v| *v += 10).or_insert(0);
+        hashmap.entry(3).and_modify(|v| *v += 10).or_insert(3);
+        assert_eq!(hashmap.read(&1, |_, v| *v), Some(11));
+        assert_eq!(hashmap.read(&3, |_, v| *v), Some(3));
+
+        // Dropping a vacant entry does not insert the key.
+        let Entry::Vacant(vacant) = hashmap.entry(4) else {
+            unreachable!();
+        };
+        assert_eq!(*vacant.key(), 4);
+        drop(vacant);
+        assert!(!hashmap.contains(&4));
+
+        // Other threads wait until the entry is dropped.
+        let updated = Arc::new(AtomicUsize::new(0));
+        let Entry::Occupied(mut occupied) = hashmap.entry(1) else {
+            unreachable!();
+        };
+        let hashmap_clone = hashmap.clone();
+        let updated_clone = updated.clone();
+        let thread_handle = thread::spawn(move || {
+            assert!(hashmap_clone.update(&1, |_, v| *v += 1).is_some());
+            updated_clone.store(1, Release);
+        });
+        thread::sleep(Duration::from_millis(16));
+        assert_eq!(updated.load(Acquire), 0);
+        *occupied.get_mut() = 100;
+        drop(occupied);
+        assert!(thread_handle.join().is_ok());
+        assert_eq!(updated.load(Acquire), 1);
+        assert_eq!(hashmap.read(&1, |_, v| *v), Some(101));
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn get_guard() {
+        let hashmap: HashMap<usize, Vec<usize>> = HashMap::default();
+        assert!(hashmap.insert(1, Vec::new()).is_ok());
+        assert!(hashmap.get(&2).is_none());
+
+        let mut entry = hashmap.get(&1).unwrap();
+        entry.push(1);
+        entry.push(2);
+        entry.retain(|v| *v != 1);
+        drop(entry);
+
+        assert_eq!(hashmap.read(&1, |_, v| v.clone()), Some(vec![2]));
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn insert_rejected() {
+        let hashmap: HashMap<usize, Box<usize>> = HashMap::default();
+        assert!(hashmap.insert(1, Box::new(1)).is_ok());
+
+        // The rejected key-value pair is returned as is.
+        let v = Box::new(2);
+        let v_ptr: *const usize = &*v;
+        let (k, v) = hashmap.insert(1, v).unwrap_err();
+        assert_eq!(k, 1);
+        assert!(std::ptr::eq(&*v, v_ptr));
+        assert_eq!(hashmap.read(&1, |_, v| **v), Some(1));
+
+        let hashmap: Arc<HashMap<usize, usize>> = Arc::new(HashMap::default());
+        let num_threads = 8;
+        for k in 0..64 {
+            let barrier = Arc::new(Barrier::new(num_threads));
+            let mut thread_handles = Vec::with_capacity(num_threads);
+            for thread_id in 0..num_threads {
+                let barrier_clone = barrier.clone();
+                let hashmap_clone = hashmap.clone();
+                thread_handles.push(thread::spawn(move || {
+                    barrier_clone.wait();
+                    match hashmap_clone.insert(k, thread_id) {
+                        Ok(()) => Some(thread_id),
+                        Err((rk, rv)) => {
+                            assert_eq!((rk, rv), (k, thread_id));
+                            None
+                        }
+                    }
+                }));
+            }
+            let inserted: Vec<usize> = thread_handles
+                .into_iter()
+                .filter_map(|t| t.join().ok().flatten())
+                .collect();
+            assert_eq!(inserted.len(), 1);
+            assert_eq!(hashmap.read(&k, |_, v| *v), Some(inserted[0]));
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn len_quiescent() {
+        let hashmap: Arc<HashMap<usize, usize>> = Arc::new(HashMap::default());
+        assert!(hashmap.is_empty());
+
+        let num_threads = 4;
+        let workload_size = 1024;
+        for round in 1..=4 {
+            let mut thread_handles = Vec::with_capacity(num_threads);
+            for thread_id in 0..num_threads {
+                let hashmap_clone = hashmap.clone();
+                thread_handles.push(thread::spawn(move || {
+                    let base = (round * num_threads + thread_id) * workload_size;
+                    for k in base..base + workload_size {
+                        assert!(hashmap_clone.insert(k, k).is_ok());
+                    }
+                    for k in base..base + workload_size / 4 {
+                        assert!(hashmap_clone.remove(&k).is_some());
+                    }
+                }));
+            }
+            for t in thread_handles {
+                assert!(t.join().is_ok());
+            }
+            assert_eq!(hashmap.len(), round * num_threads * workload_size / 4 * 3);
+            assert!(!hashmap.is_empty());
+        }
+
+        hashmap.retain(|_, _| false);
+        assert_eq!(hashmap.len(), 0);
+        assert!(hashmap.is_empty());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn scan_resize() {
+        let hashmap: Arc<HashMap<usize, usize>> = Arc::new(HashMap::default());
+        let workload_size = 1024;
+        for k in 0..workload_size {
+            assert!(hashmap.insert(k, k).is_ok());
+        }
+        let mut sum = 0;
+        hashmap.scan(|_, v| sum += *v);
+        assert_eq!(sum, workload_size * (workload_size - 1) / 2);
+        let mut snapshot = Vec::with_capacity(workload_size);
+        hashmap.scan(|k, v| snapshot.push((*k, *v)));
+        snapshot.sort_unstable();
+        assert!(snapshot.into_iter().eq((0..workload_size).map(|k| (k, k))));
+
+        let done = Arc::new(AtomicUsize::new(0));
+        let hashmap_clone = hashmap.clone();
+        let done_clone = done.clone();
+        let resizer = thread::spawn(move || {
+            while done_clone.load(Acquire) == 0 {
+                for k in workload_size..workload_size * 16 {
+                    assert!(hashmap_clone.insert(k, k).is_ok());
+                }
+                for k in workload_size..workload_size * 16 {
+                    assert!(hashmap_clone.remove(&k).is_some());
+                }
+            }
+        });
+
+        // Entries that are never removed are always visited at least once.
+        for _ in 0..64 {
+            let mut visited = BTreeSet::new();
+            hashmap.scan(|k, v| {
+                assert_eq!(k, v);
+                if *k < workload_size {
+                    visited.insert(*k);
+                }
+            });
+            assert_eq!(visited.len(), workload_size);
+        }
+        done.store(1, Release);
+        assert!(resizer.join().is_ok());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn zero_partial_hash() {
+        // Every key has `0` as its partial hash value.
+        #[derive(Default)]
+        struct ZeroHasher(u64);
+        impl Hasher for ZeroHasher {
+            fn finish(&self) -> u64 {
+                self.0.wrapping_mul(0x9e37_79b9_7f4a_7c15) & !0xff
+            }
+            fn write(&mut self, bytes: &[u8]) {
+                for b in bytes {
+                    self.0 = (self.0 << 8) | u64::from(*b);
+                }
+            }
+        }
+
+        let workload_size = 1024;
+        let hashmap: HashMap<usize, usize, std::hash::BuildHasherDefault<ZeroHasher>> =
+            HashMap::default();
+        for k in 0..workload_size {
+            assert!(hashmap.insert(k, k).is_ok());
+        }
+        assert_eq!(hashmap.len(), workload_size);
+        for k in 0..workload_size {
+            assert_eq!(hashmap.read(&k, |_, v| *v), Some(k));
+        }
+        for k in (0..workload_size).filter(|k| k % 2 == 0) {
+            assert_eq!(hashmap.remove(&k), Some((k, k)));
+        }
+        for k in 0..workload_size {
+            assert_eq!(hashmap.contains(&k), k % 2 == 1);
+        }
+    }
+
+    #[test]
+    fn fixed_hasher() {
+        #[derive(Clone, Default)]
+        struct Fnv(u64);
+        impl Hasher for Fnv {
+            fn finish(&self) -> u64 {
+                self.0
+            }
+            fn write(&mut self, bytes: &[u8]) {
+                for b in bytes {
+                    self.0 = (self.0 ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3);
+                }
+            }
+        }
+        #[derive(Clone)]
+        struct FixedState(Arc<AtomicUsize>);
+        impl std::hash::BuildHasher for FixedState {
+            type Hasher = Fnv;
+            fn build_hasher(&self) -> Fnv {
+                self.0.fetch_add(1, Relaxed);
+                Fnv(0xcbf2_9ce4_8422_2325)
+            }
+        }
+
+        let num_hashed = Arc::new(AtomicUsize::new(0));
+        let workload_size = 1024;
+        let scan_order = || {
+            let hashmap: HashMap<usize, usize, FixedState> =
+                HashMap::with_capacity_and_hasher(workload_size, FixedState(num_hashed.clone()));
+            for k in 0..workload_size {
+                assert!(hashmap.insert(k, k).is_ok());
+            }
+            let mut keys = Vec::with_capacity(workload_size);
+            hashmap.scan(|k, _| keys.push(*k));
+            keys
+        };
+
+        // The same hasher places entries in the same buckets.
+        let keys = scan_order();
+        assert_eq!(keys.len(), workload_size);
+        assert!(num_hashed.load(Relaxed) >= workload_size);
+        assert_eq!(keys, scan_order());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn reserve_no_resize() {
+        let workload_size = 4096;
+        let hashmap: HashMap<usize, usize> = HashMap::default();
+        let reserved = hashmap.reserve(workload_size / 7 * 8 + 8).unwrap();
+        assert!(hashmap.insert(0, 0).is_ok());
+        let capacity = hashmap.capacity();
+        assert!(capacity >= workload_size);
+
+        // The reserved capacity is large enough.
+        for k in 1..workload_size {
+            assert!(hashmap.insert(k, k).is_ok());
+            assert_eq!(hashmap.capacity(), capacity);
+        }
+
+        // Reserving less than the current capacity does not resize the map.
+        let another_reserved = hashmap.reserve(workload_size / 2).unwrap();
+        assert_eq!(hashmap.capacity(), capacity);
+        drop(another_reserved);
+        drop(reserved);
+        assert_eq!(hashmap.len(), workload_size);
+
+        // Reserving a lot of memory for a small map gradually grows it.
+        let hashmap: HashMap<usize, usize> = HashMap::default();
+        assert!(hashmap.insert(0, 0).is_ok());
+        let initial_capacity = hashmap.capacity();
+        let reserved = hashmap.reserve(workload_size * 64).unwrap();
+        assert!(hashmap.capacity() > initial_capacity);
+        for k in 1..workload_size {
+            assert!(hashmap.insert(k, k).is_ok());
+        }
+        for k in 0..workload_size {
+            assert_eq!(hashmap.read(&k, |_, v| *v), Some(k));
+        }
+        drop(reserved);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn drain_all() {
+        let checker = Arc::new(AtomicUsize::new(0));
+        let workload_size = 4096;
+        let hashmap: HashMap<usize, Data> = HashMap::default();
+        for k in 0..workload_size {
+            assert!(hashmap.insert(k, Data::new(k, checker.clone())).is_ok());
+        }
+        let mut drained = hashmap.drain();
+        assert_eq!(hashmap.len(), 0);
+        assert_eq!(checker.load(Relaxed), workload_size);
+        drained.sort_unstable_by_key(|(k, _)| *k);
+        assert!(drained
+            .iter()
+            .map(|(k, v)| (*k, v.data))
+            .eq((0..workload_size).map(|k| (k, k))));
+        drop(drained);
+        assert_eq!(checker.load(Relaxed), 0);
+
+        // Entries inserted during `drain` are either drained or retained.
+        let hashmap: Arc<HashMap<usize, usize>> = Arc::new(HashMap::default());
+        let hashmap_clone = hashmap.clone();
+        let inserter = thread::spawn(move || {
+            for k in 0..workload_size {
+                assert!(hashmap_clone.insert(k, k).is_ok());
+            }
+        });
+        let mut num_drained = 0;
+        while !inserter.is_finished() {
+            num_drained += hashmap.drain().len();
+        }
+        assert!(inserter.join().is_ok());
+        assert_eq!(num_drained + hashmap.len(), workload_size);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn drain_async() {
+        let hashmap: HashMap<usize, usize> = HashMap::default();
+        for k in 0..1024 {
+            assert!(hashmap.insert_async(k, k).await.is_ok());
+        }
+        let mut drained = hashmap.drain_async().await;
+        drained.sort_unstable();
+        assert!(drained.into_iter().eq((0..1024).map(|k| (k, k))));
+        assert!(hashmap.is_empty());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn extract_if() {
+        // Cloning `Data` increments the counter, therefore it proves that entries are moved.
+        let checker = Arc::new(AtomicUsize::new(0));
+        let hashmap: HashMap<usize, (u64, Data)> = HashMap::default();
+        for k in 0..1024 {
+            let inserted_at = u64::try_from(k % 64).unwrap();
+            let data = Data::new(k, checker.clone());
+            assert!(hashmap.insert(k, (inserted_at, data)).is_ok());
+        }
+        assert_eq!(checker.load(Relaxed), 1024);
+
+        // Expire entries older than the threshold.
+        let threshold = 16;
+        let mut extracted = hashmap.extract_if(|_, (inserted_at, _)| *inserted_at < threshold);
+        extracted.sort_unstable_by_key(|(k, _)| *k);
+        assert_eq!(extracted.len(), 256);
+        for (k, (inserted_at, data)) in &extracted {
+            assert!(*inserted_at < threshold);
+            assert_eq!(data.data, *k);
+        }
+        assert_eq!(checker.load(Relaxed), 1024);
+
+        assert_eq!(hashmap.len(), 768);
+        for k in 0..1024 {
+            let expired = extracted.binary_search_by_key(&k, |(k, _)| *k).is_ok();
+            assert_ne!(hashmap.contains(&k), expired);
+        }
+        drop(extracted);
+        assert_eq!(checker.load(Relaxed), 768);
+        assert!(hashmap.extract_if(|_, _| false).is_empty());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn extract_if_async() {
+        let hashmap: HashMap<usize, usize> = HashMap::default();
+        for k in 0..1024 {
+            assert!(hashmap.insert_async(k, k).await.is_ok());
+        }
+        let mut extracted = hashmap.extract_if_async(|k, _| k % 2 == 0).await;
+        extracted.sort_unstable();
+        assert!(extracted
+            .into_iter()
+            .eq((0..1024).step_by(2).map(|k| (k, k))));
+        assert_eq!(hashmap.len(), 512);
+        assert!(!hashmap.any(|k, _| k % 2 == 0));
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn add_concurrent() {
+        let num_threads = 8;
+        let num_keys = 16;
+        let num_iters = 1024;
+        let hashmap: Arc<HashMap<usize, u64>> = Arc::new(HashMap::default());
+        let barrier = Arc::new(Barrier::new(num_threads));
+        let mut thread_handles = Vec::with_capacity(num_threads);
+        for thread_id in 0..num_threads {
+            let hashmap = hashmap.clone();
+            let barrier = barrier.clone();
+            thread_handles.push(thread::spawn(move || {
+                barrier.wait();
+                for i in 0..num_iters {
+                    // Every key is shared by all the threads.
+                    hashmap.add((thread_id + i) % num_keys, 1);
+                }
+            }));
+        }
+        for thread_handle in thread_handles {
+            assert!(thread_handle.join().is_ok());
+        }
+        assert_eq!(hashmap.len(), num_keys);
+        let expected = (num_threads * num_iters / num_keys) as u64;
+        for k in 0..num_keys {
+            assert_eq!(hashmap.read(&k, |_, v| *v), Some(expected));
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn add_async() {
+        let hashmap: HashMap<usize, i64> = HashMap::default();
+        hashmap.add_async(0, -3).await;
+        hashmap.add_async(0, 5).await;
+        assert_eq!(hashmap.read_async(&0, |_, v| *v).await, Some(2));
+    }
+
+    #[test]
+    fn send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        #[derive(Default)]
+        struct LocalState(Rc<RandomState>);
+        impl BuildHasher for LocalState {
+            type Hasher = DefaultHasher;
+            fn build_hasher(&self) -> Self::Hasher {
+                self.0.build_hasher()
+            }
+        }
+
+        assert_send_sync::<HashMap<String, i32>>();
+        assert_send_sync::<HashMap<u64, Arc<String>, RandomState>>();
+        assert_send_sync::<hash_map::OccupiedEntry<String, i32>>();
+
+        // The auto traits are not implemented if the hasher cannot be shared across threads.
+        static_assertions::assert_not_impl_any!(HashMap<String, i32, LocalState>: Send, Sync);
+        let hashmap: HashMap<String, i32, LocalState> = HashMap::default();
+        assert!(hashmap.insert("a".to_string(), 1).is_ok());
+        assert_eq!(hashmap.read("a", |_, v| *v), Some(1));
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn get_cloned() {
+        let hashmap: HashMap<usize, (u32, u64)> = HashMap::default();
+        assert!(hashmap.get_cloned(&0).is_none());
+        assert!(hashmap.insert(0, (1, 2)).is_ok());
+        assert!(hashmap.insert(1, (3, 4)).is_ok());
+        assert_eq!(hashmap.get_cloned(&0), Some((1, 2)));
+        assert_eq!(hashmap.get_cloned(&1), Some((3, 4)));
+        assert!(hashmap.get_cloned(&2).is_none());
+
+        let hashmap: HashMap<usize, Data> = HashMap::default();
+        let checker = Arc::new(AtomicUsize::new(0));
+        assert!(hashmap.insert(0, Data::new(7, checker.clone())).is_ok());
+        let cloned = hashmap.get_cloned(&0).unwrap();
+        assert_eq!(cloned.data, 7);
+        assert_eq!(checker.load(Relaxed), 2);
+        drop(cloned);
+        assert_eq!(checker.load(Relaxed), 1);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn get_cloned_async() {
+        let hashmap: HashMap<usize, usize> = HashMap::default();
+        assert!(hashmap.get_cloned_async(&0).await.is_none());
+        assert!(hashmap.insert_async(0, 1).await.is_ok());
+        assert_eq!(hashmap.get_cloned_async(&0).await, Some(1));
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn get_or_insert_default() {
+        static NUM_DEFAULTS: AtomicUsize = AtomicUsize::new(0);
+        struct Counted(usize);
+        impl Default for Counted {
+            fn default() -> Self {
+                NUM_DEFAULTS.fetch_add(1, Relaxed);
+                Counted(0)
+            }
+        }
+
+        let num_threads = 8;
+        let num_iters = 64;
+        let hashmap: Arc<HashMap<usize, Counted>> = Arc::new(HashMap::default());
+        let barrier = Arc::new(Barrier::new(num_threads));
+        let mut threads = Vec::with_capacity(num_threads);
+        for _ in 0..num_threads {
+            let hashmap = hashmap.clone();
+            let barrier = barrier.clone();
+            threads.push(thread::spawn(move || {
+                barrier.wait();
+                for _ in 0..num_iters {
+                    hashmap.get_or_insert_default(0).get_mut().0 += 1;
+                }
+            }));
+        }
+        for thread in threads {
+            assert!(thread.join().is_ok());
+        }
+        assert_eq!(NUM_DEFAULTS.load(Relaxed), 1);
+        assert_eq!(hashmap.read(&0, |_, v| v.0), Some(num_threads * num_iters));
+        assert_eq!(hashmap.len(), 1);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn get_or_insert_default_async() {
+        let hashmap: HashMap<usize, Vec<usize>> = HashMap::default();
+        hashmap
+            .get_or_insert_default_async(0)
+            .await
+            .get_mut()
+            .push(1);
+        hashmap
+            .get_or_insert_default_async(0)
+            .await
+            .get_mut()
+            .push(2);
+        assert_eq!(hashmap.get_cloned_async(&0).await, Some(vec![1, 2]));
+    }
+
+    #[test]
+    fn remove_stored_key() {
+        #[derive(Debug)]
+        struct CaseInsensitive(String);
+        impl PartialEq for CaseInsensitive {
+            fn eq(&self, other: &Self) -> bool {
+                self.0.eq_ignore_ascii_case(&other.0)
+            }
+        }
+        impl Eq for CaseInsensitive {}
+        impl Hash for CaseInsensitive {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.0.to_ascii_lowercase().hash(state);
+            }
+        }
+
+        let hashmap: HashMap<CaseInsensitive, usize> = HashMap::default();
+        assert!(hashmap
+            .insert(CaseInsensitive("Tokio".to_string()), 1)
+            .is_ok());
+        assert!(hashmap
+            .insert(CaseInsensitive("tokio".to_string()), 2)
+            .is_err());
+        let (key, value) = hashmap
+            .remove(&CaseInsensitive("TOKIO".to_string()))
+            .unwrap();
+        assert_eq!(key.0, "Tokio");
+        assert_eq!(value, 1);
+        assert!(hashmap.is_empty());
+
+        assert!(hashmap
+            .insert(CaseInsensitive("Scc".to_string()), 3)
+            .is_ok());
+        let (key, value) = hashmap
+            .remove_if(&CaseInsensitive("sCC".to_string()), |v| *v == 3)
+            .unwrap();
+        assert_eq!(key.0, "Scc");
+        assert_eq!(value, 3);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn str_lookup() {
+        let hashmap: HashMap<String, i32> = HashMap::default();
+        for (k, v) in [("a", 0), ("b", 1), ("c", 2), ("d", 3)] {
+            assert!(hashmap.insert(k.to_string(), v).is_ok());
+        }
+        let key = "b".to_string();
+        assert_eq!(hashmap.bucket_index("b"), hashmap.bucket_index(&key));
+        assert_eq!(*hashmap.get("b").unwrap(), 1);
+        assert_eq!(hashmap.read("c", |_, v| *v), Some(2));
+        assert!(hashmap.contains("d"));
+        assert!(!hashmap.contains("e"));
+        assert_eq!(hashmap.replace("a", 10), Some(0));
+        assert_eq!(hashmap.remove("a"), Some(("a".to_string(), 10)));
+        assert!(hashmap.get("a").is_none());
+        assert_eq!(hashmap.len(), 3);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn for_each_mut_double() {
+        let workload_size = 4096;
+        let hashmap: HashMap<usize, usize> = HashMap::default();
+        for k in 0..workload_size {
+            assert!(hashmap.insert(k, k).is_ok());
+        }
+        let mut num_visited = 0;
+        hashmap.for_each_mut(|_, v| {
+            *v *= 2;
+            num_visited += 1;
+        });
+        assert_eq!(num_visited, workload_size);
+        assert_eq!(hashmap.len(), workload_size);
+        for k in 0..workload_size {
+            assert_eq!(hashmap.read(&k, |_, v| *v), Some(k * 2));
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn for_each_mut_async() {
+        let workload_size = 1024;
+        let hashmap: HashMap<usize, usize> = HashMap::default();
+        for k in 0..workload_size {
+            assert!(hashmap.insert_async(k, k).await.is_ok());
+        }
+        hashmap.for_each_mut_async(|k, v| *v += *k).await;
+        for k in 0..workload_size {
+            assert_eq!(hashmap.read_async(&k, |_, v| *v).await, Some(k * 2));
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn fold_sum() {
+        let workload_size = 4096;
+        let hashmap: HashMap<usize, usize> = HashMap::default();
+        assert_eq!(hashmap.fold(0, |acc, _, v| acc + *v), 0);
+        for k in 0..workload_size {
+            assert!(hashmap.insert(k, k * 2).is_ok());
+        }
+        assert_eq!(
+            hashmap.fold(0, |acc, _, v| acc + *v),
+            workload_size * (workload_size - 1)
+        );
+
+        // A histogram of the values.
+        let histogram = hashmap.fold([0_usize; 4], |mut acc, _, v| {
+            acc[*v % 8 / 2] += 1;
+            acc
+        });
+        assert_eq!(histogram, [workload_size / 4; 4]);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn fold_async() {
+        let workload_size = 1024;
+        let hashmap: HashMap<usize, usize> = HashMap::default();
+        for k in 0..workload_size {
+            assert!(hashmap.insert_async(k, k).await.is_ok());
+        }
+        assert_eq!(
+            hashmap.fold_async(0, |acc, _, v| acc + *v).await,
+            workload_size * (workload_size - 1) / 2
+        );
+    }
+
+    #[test]
+    fn retain_count() {
+        let workload_size = 1024;
+        let hashmap: HashMap<usize, usize> = HashMap::default();
+        assert_eq!(hashmap.retain_count(|_, _| false), 0);
+        for k in 0..workload_size {
+            assert!(hashmap.insert(k, k).is_ok());
+        }
+        assert_eq!(
+            hashmap.retain_count(|k, _| k % 3 != 0),
+            workload_size / 3 + 1
+        );
+        assert_eq!(hashmap.retain_count(|k, _| k % 3 != 0), 0);
+        assert_eq!(hashmap.len(), workload_size - workload_size / 3 - 1);
+
+        // The predicate can modify values.
+        let num_odd_keys = (0..workload_size)
+            .filter(|k| k % 3 != 0 && k % 2 == 1)
+            .count();
+        let num_removed = hashmap.retain_count(|_, v| {
+            *v += 1;
+            *v % 2 == 0
+        });
+        assert_eq!(
+            num_removed,
+            workload_size - workload_size / 3 - 1 - num_odd_keys
+        );
+        assert_eq!(hashmap.len(), num_odd_keys);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn retain_count_async() {
+        let workload_size = 1024;
+        let hashmap: HashMap<usize, usize> = HashMap::default();
+        for k in 0..workload_size {
+            assert!(hashmap.insert_async(k, k).await.is_ok());
+        }
+        assert_eq!(hashmap.retain_count_async(|k, _| *k >= 100).await, 100);
+        assert_eq!(hashmap.len(), workload_size - 100);
+    }
+
+    #[test]
+    fn scan_ordered() {
+        let workload_size = 1024;
+        let hashmap: HashMap<usize, usize> = HashMap::default();
+        assert!(hashmap.scan_ordered().is_empty());
+        for k in (0..workload_size).rev() {
+            assert!(hashmap.insert(k, k * 2).is_ok());
+        }
+        let entries = hashmap.scan_ordered();
+        assert_eq!(entries.len(), workload_size);
+        assert!(entries.windows(2).all(|w| w[0].0 < w[1].0));
+        assert!(entries.iter().enumerate().all(|(i, e)| *e == (i, i * 2)));
+
+        // The order does not depend on the hasher.
+        let other: HashMap<usize, usize> = HashMap::default();
+        for k in 0..workload_size {
+            assert!(other.insert(k, k * 2).is_ok());
+        }
+        assert_eq!(other.scan_ordered(), entries);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn scan_ordered_async() {
+        let workload_size = 1024;
+        let hashmap: HashMap<usize, usize> = HashMap::default();
+        for k in 0..workload_size {
+            assert!(hashmap.insert_async(workload_size - k, k).await.is_ok());
+        }
+        let entries = hashmap.scan_ordered_async().await;
+        assert_eq!(entries.len(), workload_size);
+        assert!(entries
+            .iter()
+            .enumerate()
+            .all(|(i, e)| *e == (i + 1, workload_size - i - 1)));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn par_for_each() {
+        let workload_size = 1_usize << 16;
+        let hashmap: HashMap<usize, usize> = (0..workload_size).map(|k| (k, k)).collect();
+        let num_buckets = hashmap.capacity() / 32;
+
+        // Entries in a bucket are visited by a single task.
+        let visitors: Vec<std::sync::Mutex<Option<thread::ThreadId>>> = (0..num_buckets)
+            .map(|_| std::sync::Mutex::default())
+            .collect();
+        let sum = AtomicUsize::new(0);
+        hashmap.par_for_each(|k, v| {
+            sum.fetch_add(*v, Relaxed);
+            let mut visitor = visitors[hashmap.bucket_index(k)].lock().unwrap();
+            let thread_id = thread::current().id();
+            assert_eq!(*visitor.get_or_insert(thread_id), thread_id);
+        });
+        assert_eq!(sum.load(Relaxed), hashmap.fold(0, |acc, _, v| acc + *v));
+        assert_eq!(sum.load(Relaxed), workload_size * (workload_size - 1) / 2);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn shrink_after_remove() {
+        let workload_size = 1_usize << 16;
+        let hashmap: HashMap<usize, usize> = HashMap::default();
+        for k in 0..workload_size {
+            assert!(hashmap.insert(k, k).is_ok());
+        }
+        let grown_capacity = hashmap.capacity();
+        assert!(grown_capacity >= workload_size);
+
+        // Removing most of the entries shrinks the map.
+        for k in (0..workload_size).filter(|k| k % 1024 != 0) {
+            assert_eq!(hashmap.remove(&k), Some((k, k)));
+        }
+        assert!(hashmap.capacity() < grown_capacity);
+        assert_eq!(hashmap.len(), workload_size / 1024);
+        for k in (0..workload_size).step_by(1024) {
+            assert_eq!(hashmap.read(&k, |_, v| *v), Some(k));
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn capacity_growth() {
+        let hashmap: HashMap<usize, usize> = HashMap::default();
+        let mut capacity = hashmap.capacity();
+        let mut num_resizes = 0;
+        for k in 0..1_usize << 16 {
+            let len = hashmap.len();
+            assert!(hashmap.insert(k, k).is_ok());
+            let new_capacity = hashmap.capacity();
+            if new_capacity != capacity {
+                assert!(new_capacity > capacity);
+                // The map grows at an estimated load factor of `7/8`, and the load factor right
+                // after growing is close to `1/2`; the estimate is based on sampled buckets.
+                if capacity != 0 {
+                    assert!(len >= capacity / 2, "{len} {capacity}");
+                    num_resizes += 1;
+                }
+                assert!((len + 1) * 8 <= new_capacity * 5, "{len} {new_capacity}");
+                capacity = new_capacity;
+            }
+            assert!(hashmap.len() <= capacity + capacity / 4);
+        }
+        assert!(num_resizes > 0);
+    }
+
+    #[test]
+    fn builder() {
+        #[derive(Clone)]
+        struct CountingState(Arc<AtomicUsize>);
+        impl std::hash::BuildHasher for CountingState {
+            type Hasher = std::collections::hash_map::DefaultHasher;
+            fn build_hasher(&self) -> Self::Hasher {
+                self.0.fetch_add(1, Relaxed);
+                Self::Hasher::default()
+            }
+        }
+
+        let hashmap: HashMap<usize, usize> = HashMapBuilder::new().build();
+        assert_eq!(hashmap.capacity(), 0);
+
+        // The capacity is not reduced below the specified one.
+        let hashmap: HashMap<usize, usize> = HashMapBuilder::new().capacity(1000).build();
+        assert_eq!(hashmap.capacity(), 1024);
+        for k in 0..4096 {
+            assert!(hashmap.insert(k, k).is_ok());
+        }
+        assert!(hashmap.capacity() > 1024);
+        hashmap.clear();
+        assert_eq!(hashmap.capacity(), 1024);
+
+        // The builder passes the hasher to the map; the order of method calls does not matter.
+        let num_hashed = Arc::new(AtomicUsize::new(0));
+        let hashmap: HashMap<usize, usize, CountingState> = HashMapBuilder::new()
+            .capacity(64)
+            .hasher(CountingState(num_hashed.clone()))
+            .build();
+        assert_eq!(hashmap.capacity(), 64);
+        assert!(hashmap.insert(1, 1).is_ok());
+        assert_eq!(hashmap.read(&1, |_, v| *v), Some(1));
+        assert_eq!(num_hashed.load(Relaxed), 2);
+
+        let hashmap: HashMap<usize, usize, CountingState> = HashMapBuilder::new()
+            .hasher(CountingState(num_hashed.clone()))
+            .capacity(64)
+            .build();
+        assert_eq!(hashmap.capacity(), 64);
+        assert!(hashmap.insert(1, 1).is_ok());
+        assert_eq!(num_hashed.load(Relaxed), 3);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn with_capacity_headroom() {
+        let hashmap: HashMap<usize, usize> = HashMap::with_capacity(0);
+        assert_eq!(hashmap.capacity(), 0);
+        assert!(hashmap.insert(0, 0).is_ok());
+        assert_eq!(hashmap.capacity(), 64);
+
+        // The load factor is estimated by sampling buckets, therefore the test only fills half of
+        // the capacity to leave enough room for the sampling noise.
+        for capacity in [1, 64, 65, 1000, 1024, 4095, 1 << 16] {
+            let hashmap: HashMap<usize, usize> = HashMap::with_capacity(capacity);
+            let initial_capacity = hashmap.capacity();
+            assert!(initial_capacity >= capacity);
+            assert!(initial_capacity < capacity.next_power_of_two().max(64) * 2);
+            for k in 0..initial_capacity / 2 {
+                assert!(hashmap.insert(k, k).is_ok());
+                assert_eq!(hashmap.capacity(), initial_capacity, "{capacity} {k}");
+            }
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn read_shared() {
+        let hashmap: Arc<HashMap<usize, usize>> = Arc::new(HashMap::default());
+        assert!(hashmap.insert(1, 11).is_ok());
+        assert!(hashmap.read(&2, |_, _| unreachable!()).is_none());
+
+        // The closures can only meet at the barrier if the bucket is not exclusively locked.
+        let num_threads = 4;
+        let barrier = Arc::new(Barrier::new(num_threads));
+        let mut thread_handles = Vec::with_capacity(num_threads);
+        for _ in 0..num_threads {
+            let barrier_clone = barrier.clone();
+            let hashmap_clone = hashmap.clone();
+            thread_handles.push(thread::spawn(move || {
+                let result = hashmap_clone.read(&1, |k, v| {
+                    barrier_clone.wait();
+                    *k + *v
+                });
+                assert_eq!(result, Some(12));
+            }));
+        }
+        for t in thread_handles {
+            assert!(t.join().is_ok());
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn insert_sync_cross_thread() {
+        let hashmap: Arc<HashMap<usize, usize>> = Arc::new(HashMap::default());
+        let num_threads = 8;
+        let workload_size = 4096;
+        let barrier = Arc::new(Barrier::new(num_threads));
+        let mut thread_handles = Vec::with_capacity(num_threads);
+        for thread_id in 0..num_threads {
+            let barrier_clone = barrier.clone();
+            let hashmap_clone = hashmap.clone();
+            thread_handles.push(thread::spawn(move || {
+                barrier_clone.wait();
+                for id in (thread_id * workload_size)..((thread_id + 1) * workload_size) {
+                    assert!(hashmap_clone.insert(id, id).is_ok());
+                }
+                barrier_clone.wait();
+
+                // Entries inserted by other threads are all visible.
+                for id in 0..num_threads * workload_size {
+                    assert_eq!(hashmap_clone.read(&id, |_, v| *v), Some(id));
+                }
+            }));
+        }
+
+        for t in thread_handles {
+            assert!(t.join().is_ok());
         }
-
-        assert_eq!(hashmap.len(), 0);
+        assert_eq!(hashmap.len(), num_threads * workload_size);
     }
 
     #[cfg_attr(miri, ignore)]
@@ -562,7 +2100,7 @@ mod hashmap_test {
                                 }
                             })
                             .await;
-                    };
+                    }
                     assert_eq!(removed, workload_size);
                     assert!(!hashmap_clone.any_async(|k, _| range.contains(k)).await);
                 }));
@@ -633,7 +2171,7 @@ mod hashmap_test {
                                 }
                             })
                             .await;
-                    };
+                    }
                     assert_eq!(removed, workload_size);
 
                     assert!(!hashmap_clone.any(|k, _| range.contains(k)));
@@ -875,6 +2413,7 @@ mod hashmap_test {
 #[cfg(not(feature = "loom"))]
 #[cfg(test)]
 mod hashindex_test {
+    use super::Collide;
     use crate::ebr::Guard;
     use crate::hash_index::{self, Iter};
     use crate::{Equivalent, HashIndex};
@@ -885,7 +2424,7 @@ mod hashindex_test {
     use std::panic::UnwindSafe;
     use std::rc::Rc;
     use std::sync::atomic::Ordering::{Acquire, Relaxed, Release};
-    use std::sync::atomic::{fence, AtomicU64, AtomicUsize};
+    use std::sync::atomic::{fence, AtomicBool, AtomicU64, AtomicUsize};
     use std::sync::Arc;
     use std::thread;
     use tokio::sync::Barrier as AsyncBarrier;
@@ -927,7 +2466,7 @@ mod hashindex_test {
 
     impl Hash for EqTest {
         fn hash<H: Hasher>(&self, state: &mut H) {
-            self.0.hash(state)
+            self.0.hash(state);
         }
     }
 
@@ -984,6 +2523,113 @@ mod hashindex_test {
         }
     }
 
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn peek_while_locked() {
+        let hashindex: Arc<HashIndex<usize, (usize, usize)>> = Arc::new(HashIndex::default());
+        let num_keys = 16;
+        for k in 0..num_keys {
+            assert!(hashindex.insert(k, (0, usize::MAX)).is_ok());
+        }
+
+        // Readers make progress while the writer exclusively owns the bucket.
+        let entry = hashindex.get(&0).unwrap();
+        let hashindex_clone = hashindex.clone();
+        let reader = thread::spawn(move || {
+            for _ in 0..1024 {
+                assert_eq!(hashindex_clone.peek_with(&0, |_, v| *v), Some((0, usize::MAX)));
+            }
+        });
+        assert!(reader.join().is_ok());
+        drop(entry);
+
+        // Readers never observe a partially updated entry.
+        let done = Arc::new(AtomicUsize::new(0));
+        let mut thread_handles = Vec::new();
+        for _ in 0..2 {
+            let hashindex_clone = hashindex.clone();
+            let done_clone = done.clone();
+            thread_handles.push(thread::spawn(move || {
+                let mut num_reads = 0;
+                loop {
+                    for k in 0..num_keys {
+                        let (a, b) = hashindex_clone.peek_with(&k, |_, v| *v).unwrap();
+                        assert_eq!(a, !b);
+                        num_reads += 1;
+                    }
+                    if done_clone.load(Acquire) != 0 {
+                        break;
+                    }
+                }
+                num_reads
+            }));
+        }
+        for i in 1..=num_keys * 64 {
+            let entry = hashindex.get(&(i % num_keys)).unwrap();
+            entry.update((i, !i));
+        }
+        done.store(1, Release);
+        for t in thread_handles {
+            assert!(t.join().unwrap() > 0);
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn linked_bucket_reclaim() {
+        static INST_CNT: AtomicUsize = AtomicUsize::new(0);
+
+        // All the keys are mapped to a couple of buckets to form long linked lists.
+        let hashindex: Arc<HashIndex<Collide, (usize, R)>> = Arc::new(HashIndex::default());
+        let num_writers = 2;
+        let num_readers = 2;
+        let workload_size = 64;
+        let done = Arc::new(AtomicUsize::new(0));
+        let mut thread_handles = Vec::with_capacity(num_writers + num_readers);
+        for thread_id in 0..num_writers {
+            let hashindex_clone = hashindex.clone();
+            let done_clone = done.clone();
+            thread_handles.push(thread::spawn(move || {
+                let range = (thread_id * workload_size)..((thread_id + 1) * workload_size);
+                for _ in 0..64 {
+                    for k in range.clone() {
+                        assert!(hashindex_clone
+                            .insert(Collide(k, 2), (k, R::new(&INST_CNT)))
+                            .is_ok());
+                    }
+                    for k in range.clone() {
+                        assert!(hashindex_clone.remove(&Collide(k, 2)));
+                    }
+                }
+                done_clone.fetch_add(1, Release);
+            }));
+        }
+        for _ in 0..num_readers {
+            let hashindex_clone = hashindex.clone();
+            let done_clone = done.clone();
+            thread_handles.push(thread::spawn(move || {
+                while done_clone.load(Acquire) != num_writers {
+                    for k in 0..num_writers * workload_size {
+                        // Removed entries and linked buckets must stay valid while being read.
+                        if let Some(v) = hashindex_clone.peek_with(&Collide(k, 2), |_, v| v.0) {
+                            assert_eq!(v, k);
+                        }
+                    }
+                }
+            }));
+        }
+        for t in thread_handles {
+            assert!(t.join().is_ok());
+        }
+        assert_eq!(hashindex.len(), 0);
+        drop(hashindex);
+
+        while INST_CNT.load(Relaxed) != 0 {
+            Guard::new().accelerate();
+            thread::yield_now();
+        }
+    }
+
     #[cfg_attr(miri, ignore)]
     #[tokio::test]
     async fn clear_async() {
@@ -1008,13 +2654,57 @@ mod hashindex_test {
         }
     }
 
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn peek_guard_resize() {
+        let num_readers = 4;
+        let workload_size = 256;
+        let hashindex: Arc<HashIndex<usize, usize>> = Arc::new(HashIndex::default());
+        for k in 0..workload_size {
+            assert!(hashindex.insert(k, k).is_ok());
+        }
+
+        let done = Arc::new(AtomicBool::new(false));
+        let mut thread_handles = Vec::with_capacity(num_readers);
+        for _ in 0..num_readers {
+            let hashindex = hashindex.clone();
+            let done = done.clone();
+            thread_handles.push(thread::spawn(move || {
+                // A single `Guard` is used for all the lookups overlapping resizes.
+                let guard = Guard::new();
+                let mut value_refs = Vec::new();
+                while !done.load(Acquire) {
+                    for k in 0..workload_size {
+                        let value_ref = hashindex.peek(&k, &guard).unwrap();
+                        assert_eq!(*value_ref, k);
+                        if value_refs.len() < workload_size * 16 {
+                            value_refs.push((k, value_ref));
+                        }
+                    }
+                }
+                assert!(value_refs.iter().all(|(k, v)| *k == **v));
+            }));
+        }
+
+        for k in workload_size..workload_size * 256 {
+            assert!(hashindex.insert(k, k).is_ok());
+        }
+        for k in workload_size..workload_size * 256 {
+            assert!(hashindex.remove(&k));
+        }
+        done.store(true, Release);
+        for thread_handle in thread_handles {
+            assert!(thread_handle.join().is_ok());
+        }
+        assert_eq!(hashindex.len(), workload_size);
+    }
+
     #[test]
     fn from_iter() {
         static INST_CNT: AtomicUsize = AtomicUsize::new(0);
 
         let workload_size = 256;
         let hashindex = (0..workload_size)
-            .into_iter()
             .map(|k| (k / 2, R::new(&INST_CNT)))
             .collect::<HashIndex<usize, R>>();
         assert_eq!(hashindex.len(), workload_size / 2);
@@ -1520,7 +3210,7 @@ mod hashindex_test {
                                 }
                             })
                             .await;
-                    };
+                    }
                     assert_eq!(removed, workload_size);
                     assert!(!hashindex_clone
                         .iter(&Guard::new())
@@ -1544,6 +3234,8 @@ mod hashset_test {
     use std::hash::{Hash, Hasher};
     use std::panic::UnwindSafe;
     use std::rc::Rc;
+    use std::sync::Arc;
+    use std::thread;
 
     static_assertions::assert_not_impl_all!(HashSet<Rc<String>>: Send, Sync);
     static_assertions::assert_impl_all!(HashSet<String>: Send, Sync, UnwindSafe);
@@ -1560,7 +3252,7 @@ mod hashset_test {
 
     impl Hash for EqTest {
         fn hash<H: Hasher>(&self, state: &mut H) {
-            self.0.hash(state)
+            self.0.hash(state);
         }
     }
 
@@ -1572,11 +3264,52 @@ mod hashset_test {
         assert!(hashset.contains("HELLO"));
     }
 
+    #[test]
+    fn insert_contains_remove() {
+        let hashset: HashSet<usize> = HashSet::default();
+        assert!(hashset.insert(1).is_ok());
+        assert_eq!(hashset.insert(1), Err(1));
+        assert!(hashset.contains(&1));
+        assert!(!hashset.contains(&2));
+        assert_eq!(hashset.remove(&1), Some(1));
+        assert_eq!(hashset.remove(&1), None);
+        assert!(!hashset.contains(&1));
+        assert!(hashset.insert(1).is_ok());
+        assert_eq!(hashset.len(), 1);
+        hashset.clear();
+        assert!(hashset.is_empty());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn insert_dedup() {
+        let hashset: Arc<HashSet<usize>> = Arc::new(HashSet::default());
+        let num_threads = 8;
+        let workload_size = 1024;
+        let mut thread_handles = Vec::with_capacity(num_threads);
+        for thread_id in 0..num_threads {
+            let hashset_clone = hashset.clone();
+            thread_handles.push(thread::spawn(move || {
+                // Overlaps with the range of the next thread.
+                let base = thread_id * workload_size / 2;
+                (base..base + workload_size)
+                    .filter(|k| hashset_clone.insert(*k).is_ok())
+                    .count()
+            }));
+        }
+        let num_inserted: usize = thread_handles
+            .into_iter()
+            .filter_map(|t| t.join().ok())
+            .sum();
+        let num_unique = (num_threads + 1) * workload_size / 2;
+        assert_eq!(num_inserted, num_unique);
+        assert_eq!(hashset.len(), num_unique);
+    }
+
     #[test]
     fn from_iter() {
         let workload_size = 256;
         let hashset = (0..workload_size)
-            .into_iter()
             .map(|k| k / 2)
             .collect::<HashSet<usize>>();
         assert_eq!(hashset.len(), workload_size / 2);
@@ -1608,6 +3341,7 @@ mod hashset_test {
 #[cfg(not(feature = "loom"))]
 #[cfg(test)]
 mod hashcache_test {
+    use super::Collide;
     use crate::hash_cache;
     use crate::{Equivalent, HashCache};
     use proptest::prelude::*;
@@ -1658,7 +3392,7 @@ mod hashcache_test {
 
     impl Hash for EqTest {
         fn hash<H: Hasher>(&self, state: &mut H) {
-            self.0.hash(state)
+            self.0.hash(state);
         }
     }
 
@@ -1670,6 +3404,52 @@ mod hashcache_test {
         assert!(hashcache.contains("HELLO"));
     }
 
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn bounded_capacity() {
+        let maximum_capacity = 256;
+        let hashcache: HashCache<usize, usize> = HashCache::with_capacity(0, maximum_capacity);
+        for k in 0..maximum_capacity * 16 {
+            assert!(hashcache.put(k, k).is_ok());
+            assert!(hashcache.capacity() <= maximum_capacity);
+            assert!(hashcache.len() <= maximum_capacity);
+        }
+
+        // Existing entries can still be updated in place.
+        let mut num_updated = 0;
+        hashcache.retain(|k, v| {
+            *v = *k + 1;
+            num_updated += 1;
+            true
+        });
+        assert_eq!(num_updated, hashcache.len());
+        hashcache.scan(|k, v| assert_eq!(*k + 1, *v));
+        assert!(hashcache.len() <= maximum_capacity);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn lru_eviction() {
+        // All the keys are put into the same bucket that can hold `32` entries.
+        let hashcache: HashCache<Collide, usize> = HashCache::with_capacity(0, 64);
+        for k in 0..32 {
+            assert_eq!(hashcache.put(Collide(k, 1), k), Ok(None));
+        }
+
+        // Recently accessed keys survive eviction.
+        for k in (0..32).step_by(2) {
+            assert!(hashcache.get(&Collide(k, 1)).is_some());
+        }
+        for k in 32..48 {
+            let evicted = hashcache.put(Collide(k, 1), k).unwrap().unwrap();
+            assert_eq!(evicted.0 .0 % 2, 1);
+        }
+        for k in 0..32 {
+            assert_eq!(hashcache.contains(&Collide(k, 1)), k % 2 == 0);
+        }
+        assert_eq!(hashcache.len(), 32);
+    }
+
     #[test]
     fn put_drop() {
         static INST_CNT: AtomicUsize = AtomicUsize::new(0);
@@ -1988,7 +3768,8 @@ mod treeindex_test {
     use std::borrow::Borrow;
     use std::cmp::Ordering;
     use std::collections::BTreeSet;
-    use std::ops::RangeInclusive;
+    use std::ops::Bound::{Excluded, Included, Unbounded};
+    use std::ops::{Bound, RangeInclusive};
     use std::panic::UnwindSafe;
     use std::rc::Rc;
     use std::sync::atomic::Ordering::{Acquire, Relaxed, Release};
@@ -2717,7 +4498,9 @@ mod treeindex_test {
                 assert!(tree.remove(&i));
                 removed.store(i, Release);
             }
-            thread_handles.into_iter().for_each(|t| t.join().unwrap());
+            for t in thread_handles {
+                t.join().unwrap();
+            }
         }
     }
 
@@ -2778,6 +4561,113 @@ mod treeindex_test {
         );
     }
 
+    #[test]
+    fn range_bounds() {
+        let tree: TreeIndex<usize, usize> = TreeIndex::default();
+        let keys: BTreeSet<usize> = (0..1024).step_by(3).collect();
+        for k in &keys {
+            assert!(tree.insert(*k, *k).is_ok());
+        }
+
+        let guard = Guard::new();
+        let check = |range: (Bound<usize>, Bound<usize>)| {
+            let result: Vec<usize> = tree.range(range, &guard).map(|(k, _)| *k).collect();
+            let expected: Vec<usize> = keys.range(range).copied().collect();
+            assert_eq!(result, expected, "{range:?}");
+        };
+        for (lower, upper) in [
+            (0, 1023),
+            (3, 30),
+            (4, 30),
+            (1, 2),
+            (999, 2048),
+            (2048, 4096),
+        ] {
+            check((Included(lower), Excluded(upper)));
+            check((Included(lower), Included(upper)));
+            check((Excluded(lower), Excluded(upper)));
+            check((Excluded(lower), Included(upper)));
+            check((Included(lower), Unbounded));
+            check((Excluded(lower), Unbounded));
+            check((Unbounded, Included(upper)));
+            check((Unbounded, Excluded(upper)));
+        }
+        check((Unbounded, Unbounded));
+        check((Included(6), Included(6)));
+        check((Included(6), Excluded(6)));
+        check((Excluded(6), Included(6)));
+        assert_eq!(tree.range((Included(30), Excluded(3)), &guard).count(), 0);
+        assert_eq!(tree.range(12..=12, &guard).count(), 1);
+        assert_eq!(tree.range(..0, &guard).count(), 0);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn range_insert_remove() {
+        // Even keys are never removed, whereas odd keys are repeatedly inserted and removed.
+        let workload_size = 4096;
+        let num_threads = 4;
+        let tree: Arc<TreeIndex<usize, usize>> = Arc::new(TreeIndex::default());
+        for k in (0..workload_size).step_by(2) {
+            assert!(tree.insert(k, k).is_ok());
+        }
+        let barrier = Arc::new(Barrier::new(num_threads + 1));
+        let done = Arc::new(AtomicBool::new(false));
+        let mut thread_handles = Vec::with_capacity(num_threads);
+        for thread_id in 0..num_threads {
+            let tree = tree.clone();
+            let barrier = barrier.clone();
+            thread_handles.push(thread::spawn(move || {
+                barrier.wait();
+                for _ in 0..4 {
+                    for k in (thread_id * 2 + 1..workload_size).step_by(num_threads * 2) {
+                        assert!(tree.insert(k, k).is_ok());
+                    }
+                    for k in (thread_id * 2 + 1..workload_size).step_by(num_threads * 2) {
+                        assert!(tree.remove(&k));
+                    }
+                }
+            }));
+        }
+        let reader = {
+            let tree = tree.clone();
+            let barrier = barrier.clone();
+            let done = done.clone();
+            thread::spawn(move || {
+                barrier.wait();
+                while !done.load(Acquire) {
+                    for lower in (0..workload_size).step_by(workload_size / 8) {
+                        let upper = lower + workload_size / 4;
+                        let guard = Guard::new();
+                        let mut prev = None;
+                        let mut num_even_keys = 0;
+                        for (k, v) in tree.range(lower..upper, &guard) {
+                            assert_eq!(k, v);
+                            assert!(*k >= lower && *k < upper);
+                            assert!(prev.map_or(true, |p| p < *k));
+                            if k % 2 == 0 {
+                                num_even_keys += 1;
+                            }
+                            prev.replace(*k);
+                        }
+                        assert_eq!(num_even_keys, (upper.min(workload_size) - lower) / 2);
+                    }
+                }
+            })
+        };
+        for handle in thread_handles {
+            handle.join().unwrap();
+        }
+        done.store(true, Release);
+        reader.join().unwrap();
+
+        let guard = Guard::new();
+        assert!(tree
+            .iter(&guard)
+            .map(|(k, _)| *k)
+            .eq((0..workload_size).step_by(2)));
+    }
+
     proptest! {
         #[cfg_attr(miri, ignore)]
         #[test]
@@ -2888,7 +4778,6 @@ mod bag_test {
 
         let workload_size = 16;
         let bag = (0..workload_size)
-            .into_iter()
             .map(|_| R::new(&INST_CNT))
             .collect::<Bag<R>>();
         assert_eq!(bag.len(), workload_size);
@@ -3025,7 +4914,7 @@ mod bag_test {
 #[cfg(test)]
 mod queue_test {
     use crate::ebr::Guard;
-    use crate::Queue;
+    use crate::{LinkedList, Queue};
     use std::panic::UnwindSafe;
     use std::rc::Rc;
     use std::sync::atomic::AtomicUsize;
@@ -3076,7 +4965,6 @@ mod queue_test {
 
         let workload_size = 16;
         let queue = (0..workload_size)
-            .into_iter()
             .map(|i| R::new(&INST_CNT, i, i))
             .collect::<Queue<R>>();
         assert_eq!(queue.len(), workload_size);
@@ -3235,6 +5123,199 @@ mod queue_test {
         // Expect `cnt <= 50`.
         println!("{cnt}");
     }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn pop_blocking() {
+        let num_consumers = 4;
+        let queue: Arc<Queue<usize>> = Arc::new(Queue::default());
+        let barrier = Arc::new(Barrier::new(num_consumers + 1));
+        let num_popped = Arc::new(AtomicUsize::new(0));
+        let mut thread_handles = Vec::with_capacity(num_consumers);
+        for _ in 0..num_consumers {
+            let queue = queue.clone();
+            let barrier = barrier.clone();
+            let num_popped = num_popped.clone();
+            thread_handles.push(thread::spawn(move || {
+                barrier.wait();
+                let popped = **queue.pop_blocking();
+                num_popped.fetch_add(1, Relaxed);
+                popped
+            }));
+        }
+        barrier.wait();
+
+        // A single entry wakes up one of the consumers.
+        queue.push(0);
+        while num_popped.load(Relaxed) == 0 {
+            thread::yield_now();
+        }
+        thread::sleep(std::time::Duration::from_millis(10));
+        assert_eq!(num_popped.load(Relaxed), 1);
+
+        for v in 1..num_consumers {
+            queue.push(v);
+        }
+        let mut popped: Vec<usize> = thread_handles
+            .into_iter()
+            .map(|t| t.join().unwrap())
+            .collect();
+        popped.sort_unstable();
+        assert!(popped.into_iter().eq(0..num_consumers));
+        assert!(queue.is_empty());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn mpmc_blocking() {
+        const NUM_PRODUCERS: usize = 4;
+        const NUM_CONSUMERS: usize = 4;
+        let workload_size = 4096;
+
+        // Producers are blocked by a full `Queue` if it is bounded.
+        for capacity in [usize::MAX, NUM_CONSUMERS] {
+            let queue: Arc<Queue<(usize, usize)>> = Arc::new(Queue::with_capacity(capacity));
+            let barrier = Arc::new(Barrier::new(NUM_PRODUCERS + NUM_CONSUMERS));
+            let mut producers = Vec::with_capacity(NUM_PRODUCERS);
+            for task_id in 0..NUM_PRODUCERS {
+                let queue = queue.clone();
+                let barrier = barrier.clone();
+                producers.push(thread::spawn(move || {
+                    barrier.wait();
+                    for seq in 0..workload_size {
+                        queue.push((task_id, seq));
+                    }
+                }));
+            }
+            let mut consumers = Vec::with_capacity(NUM_CONSUMERS);
+            for _ in 0..NUM_CONSUMERS {
+                let queue = queue.clone();
+                let barrier = barrier.clone();
+                consumers.push(thread::spawn(move || {
+                    barrier.wait();
+                    let mut popped = Vec::with_capacity(workload_size);
+                    for _ in 0..workload_size * NUM_PRODUCERS / NUM_CONSUMERS {
+                        let entry = queue.pop_blocking();
+                        popped.push(**entry);
+                    }
+                    popped
+                }));
+            }
+            for t in producers {
+                assert!(t.join().is_ok());
+            }
+
+            // Every entry is popped exactly once, and entries from a producer are popped in order.
+            let mut popped_seq = [0_usize; NUM_PRODUCERS];
+            let mut all_popped = Vec::with_capacity(workload_size * NUM_PRODUCERS);
+            for t in consumers {
+                let popped = t.join().unwrap();
+                let mut last_seq = [None; NUM_PRODUCERS];
+                for (task_id, seq) in &popped {
+                    assert!(last_seq[*task_id].map_or(true, |s| s < *seq));
+                    last_seq[*task_id].replace(*seq);
+                    popped_seq[*task_id] += 1;
+                }
+                all_popped.extend(popped);
+            }
+            assert_eq!(popped_seq, [workload_size; NUM_PRODUCERS]);
+            all_popped.sort_unstable();
+            all_popped.dedup();
+            assert_eq!(all_popped.len(), workload_size * NUM_PRODUCERS);
+            assert!(queue.is_empty());
+        }
+    }
+
+    #[test]
+    fn bounded() {
+        let queue: Queue<usize> = Queue::with_capacity(4);
+        for v in 0..4 {
+            assert!(queue.try_push(v).is_ok());
+        }
+        assert_eq!(queue.try_push(4).err(), Some(4));
+        assert_eq!(queue.push_if(4, |_| true).err(), Some(4));
+        assert_eq!(queue.len(), 4);
+
+        // A failed conditional push does not consume room.
+        assert_eq!(queue.pop().map(|e| **e), Some(0));
+        assert!(queue.push_if(4, |_| false).is_err());
+        assert!(queue.push_if(4, |_| true).is_ok());
+        assert!(queue.try_push(5).is_err());
+
+        let queue_clone = queue.clone();
+        assert_eq!(queue_clone.len(), 4);
+        assert!(queue_clone.try_push(5).is_err());
+        while queue_clone.pop().is_some() {}
+        assert!(queue_clone.try_push(5).is_ok());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn bounded_delete_out_of_order() {
+        let capacity = 4;
+        let queue: Arc<Queue<usize>> = Arc::new(Queue::with_capacity(capacity));
+        let entries: Vec<_> = (0..capacity).map(|v| queue.try_push(v).unwrap()).collect();
+        assert!(queue.try_push(capacity).is_err());
+
+        // Deleting an entry does not make room for another entry, and popping skips it.
+        for index in [2, 0] {
+            assert!(entries[index].delete_self(Relaxed));
+            assert!(!entries[index].delete_self(Relaxed));
+            assert!(queue.try_push(capacity).is_err());
+        }
+
+        // A blocked producer is woken up when an entry is popped.
+        let producer = {
+            let queue = queue.clone();
+            thread::spawn(move || **queue.push(capacity))
+        };
+        thread::sleep(std::time::Duration::from_millis(10));
+        assert_eq!(queue.pop().map(|e| **e), Some(1));
+        assert_eq!(producer.join().unwrap(), capacity);
+
+        assert_eq!(queue.pop().map(|e| **e), Some(3));
+        assert_eq!(queue.pop().map(|e| **e), Some(capacity));
+        assert!(queue.pop().is_none());
+        assert!(queue.try_push(capacity + 1).is_ok());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn bounded_slow_consumer() {
+        let capacity = 4;
+        let workload_size = 64;
+        let queue: Arc<Queue<usize>> = Arc::new(Queue::with_capacity(capacity));
+        let num_pushed = Arc::new(AtomicUsize::new(0));
+        let producer = {
+            let queue = queue.clone();
+            let num_pushed = num_pushed.clone();
+            thread::spawn(move || {
+                for v in 0..workload_size {
+                    queue.push(v);
+                    num_pushed.fetch_add(1, Relaxed);
+                }
+            })
+        };
+
+        // The producer blocks once the `Queue` is full.
+        while num_pushed.load(Relaxed) < capacity {
+            thread::yield_now();
+        }
+        thread::sleep(std::time::Duration::from_millis(10));
+        assert_eq!(num_pushed.load(Relaxed), capacity);
+        assert_eq!(queue.len(), capacity);
+
+        for v in 0..workload_size {
+            assert!(queue.len() <= capacity);
+            assert_eq!(**queue.pop_blocking(), v);
+            if v % 8 == 0 {
+                thread::sleep(std::time::Duration::from_millis(1));
+            }
+        }
+        assert!(producer.join().is_ok());
+        assert_eq!(num_pushed.load(Relaxed), workload_size);
+        assert!(queue.is_empty());
+    }
 }
 
 #[cfg(not(feature = "loom"))]
@@ -3280,7 +5361,7 @@ mod stack_test {
     #[test]
     fn from_iter() {
         let workload_size = 16;
-        let stack = (0..workload_size).into_iter().collect::<Stack<usize>>();
+        let stack = (0..workload_size).collect::<Stack<usize>>();
         assert_eq!(stack.len(), workload_size);
         for i in (0..workload_size).rev() {
             assert_eq!(stack.pop().map(|e| **e), Some(i));
@@ -3425,9 +5506,11 @@ mod random_failure_test {
     use crate::hash_map::Entry;
     use crate::{HashCache, HashIndex, HashMap, TreeIndex};
     use std::any::Any;
-    use std::panic::catch_unwind;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
     use std::sync::atomic::Ordering::{AcqRel, Relaxed};
     use std::sync::atomic::{AtomicBool, AtomicUsize};
+    use std::sync::Arc;
+    use std::thread;
 
     struct R(&'static AtomicUsize, &'static AtomicBool, bool);
     impl R {
@@ -3590,6 +5673,49 @@ mod random_failure_test {
             std::thread::yield_now();
         }
     }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn update_panic_safety() {
+        let hashmap: Arc<HashMap<usize, usize>> = Arc::new(HashMap::default());
+        let num_keys = 4;
+        for k in 0..num_keys {
+            assert!(hashmap.insert(k, 0).is_ok());
+        }
+
+        let num_threads = 8;
+        let workload_size = 256;
+        let mut thread_handles = Vec::with_capacity(num_threads);
+        for _ in 0..num_threads {
+            let hashmap_clone = hashmap.clone();
+            thread_handles.push(thread::spawn(move || {
+                let mut num_updated = 0;
+                for i in 0..workload_size {
+                    let result: Result<Option<()>, Box<dyn Any + Send>> =
+                        catch_unwind(AssertUnwindSafe(|| {
+                            hashmap_clone.update(&(i % num_keys), |_, v| {
+                                // The bucket must be unlocked even if the closure panics.
+                                assert!(rand::random::<u8>() % 4 != 0);
+                                *v += 1;
+                            })
+                        }));
+                    if let Ok(updated) = result {
+                        assert!(updated.is_some());
+                        num_updated += 1;
+                    }
+                }
+                num_updated
+            }));
+        }
+
+        let num_updated: usize = thread_handles
+            .into_iter()
+            .filter_map(|t| t.join().ok())
+            .sum();
+        let mut sum = 0;
+        hashmap.scan(|_, v| sum += *v);
+        assert_eq!(sum, num_updated);
+    }
 }
 
 #[cfg(feature = "serde")]
@@ -3597,7 +5723,7 @@ mod random_failure_test {
 mod serde_test {
     use crate::{HashCache, HashIndex, HashMap, HashSet, TreeIndex};
 
-    use serde_test::{assert_tokens, Token};
+    use serde_test::{assert_de_tokens, assert_tokens, Token};
 
     #[test]
     fn hashmap() {
@@ -3614,6 +5740,45 @@ mod serde_test {
         );
     }
 
+    #[test]
+    fn hashmap_composite_key() {
+        let hashmap: HashMap<(u8, String), String> = HashMap::new();
+        assert!(hashmap.insert((1, "a".to_string()), "b".to_string()).is_ok());
+        assert_tokens(
+            &hashmap,
+            &[
+                Token::Map { len: Some(1) },
+                Token::Tuple { len: 2 },
+                Token::U8(1),
+                Token::Str("a"),
+                Token::TupleEnd,
+                Token::Str("b"),
+                Token::MapEnd,
+            ],
+        );
+    }
+
+    #[test]
+    fn hashmap_duplicate_keys() {
+        // The last value wins.
+        let hashmap: HashMap<u64, i16> = HashMap::new();
+        assert!(hashmap.insert(2, -7).is_ok());
+        assert!(hashmap.insert(3, 1).is_ok());
+        assert_de_tokens(
+            &hashmap,
+            &[
+                Token::Map { len: Some(3) },
+                Token::U64(2),
+                Token::I16(-6),
+                Token::U64(3),
+                Token::I16(1),
+                Token::U64(2),
+                Token::I16(-7),
+                Token::MapEnd,
+            ],
+        );
+    }
+
     #[test]
     fn hashset() {
         let hashset: HashSet<u64> = HashSet::new();
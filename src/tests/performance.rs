@@ -666,7 +666,7 @@ mod benchmark {
         treeindex_benchmark::<usize>(65536, vec![1, 2, 4]);
     }
 
-    #[ignore]
+    #[ignore = "full-scale benchmark"]
     #[test]
     fn full_scale_benchmarks_sync() {
         hashmap_benchmark::<usize>(
@@ -1331,7 +1331,7 @@ mod benchmark_async {
             };
             let (duration, total_num_operations) =
                 treeindex_perform(num_tasks, 0, treeindex.clone(), scan.clone()).await;
-            println!("treeindex-scan: {num_tasks}, {duration:?}, {total_num_operations}",);
+            println!("treeindex-scan: {num_tasks}, {duration:?}, {total_num_operations}");
 
             // 3. read-local
             let read = Workload {
@@ -1346,7 +1346,7 @@ mod benchmark_async {
             };
             let (duration, total_num_operations) =
                 treeindex_perform(num_tasks, 0, treeindex.clone(), read.clone()).await;
-            println!("treeindex-read-local: {num_tasks}, {duration:?}, {total_num_operations}",);
+            println!("treeindex-read-local: {num_tasks}, {duration:?}, {total_num_operations}");
 
             // 4. remove-local
             let remove = Workload {
@@ -1361,7 +1361,7 @@ mod benchmark_async {
             };
             let (duration, total_num_operations) =
                 treeindex_perform(num_tasks, 0, treeindex.clone(), remove.clone()).await;
-            println!("treeindex-remove-local: {num_tasks}, {duration:?}, {total_num_operations}",);
+            println!("treeindex-remove-local: {num_tasks}, {duration:?}, {total_num_operations}");
             assert_eq!(treeindex.len(), 0);
 
             // 5. insert-local-remote
@@ -1404,7 +1404,7 @@ mod benchmark_async {
                 mixed.clone(),
             )
             .await;
-            println!("treeindex-mixed: {num_tasks}, {duration:?}, {total_num_operations}",);
+            println!("treeindex-mixed: {num_tasks}, {duration:?}, {total_num_operations}");
             assert_eq!(treeindex.len(), workload_size * num_tasks);
 
             // 7. remove-local-remote
@@ -1434,7 +1434,7 @@ mod benchmark_async {
         treeindex_benchmark(65536, vec![1, 2, 4]).await;
     }
 
-    #[ignore]
+    #[ignore = "full-scale benchmark"]
     #[tokio::test(flavor = "multi_thread", worker_threads = 96)]
     async fn full_scale_benchmarks_async() {
         hashmap_benchmark(
@@ -69,6 +69,7 @@ pub struct Iter<'t, 'g, K, V> {
 }
 
 /// An iterator over a sub-range of entries in a [`TreeIndex`].
+#[allow(clippy::struct_field_names)]
 pub struct Range<'t, 'g, K, V, Q: ?Sized, R: RangeBounds<Q>> {
     root: &'t AtomicShared<Node<K, V>>,
     leaf_scanner: Option<Scanner<'g, K, V>>,
@@ -1246,7 +1246,6 @@ mod test {
             match result.unwrap() {
                 InsertResult::Success => {
                     assert_eq!(leaf_node.search_entry(&k, &guard), Some((&k, &k)));
-                    continue;
                 }
                 InsertResult::Duplicate(..)
                 | InsertResult::Frozen(..)
@@ -1314,9 +1313,7 @@ mod test {
                                         max_key.replace(id);
                                         break;
                                     }
-                                    InsertResult::Frozen(..) | InsertResult::Retry(..) => {
-                                        continue;
-                                    }
+                                    InsertResult::Frozen(..) | InsertResult::Retry(..) => (),
                                     _ => unreachable!(),
                                 }
                             }
@@ -1326,13 +1323,13 @@ mod test {
                         }
                     }
                     for id in range.clone() {
-                        if max_key.map_or(false, |m| m == id) {
+                        if max_key == Some(id) {
                             break;
                         }
                         assert_eq!(leaf_node_clone.search_entry(&id, &guard), Some((&id, &id)));
                     }
                     for id in range {
-                        if max_key.map_or(false, |m| m == id) {
+                        if max_key == Some(id) {
                             break;
                         }
                         loop {
@@ -1403,7 +1400,7 @@ mod test {
                                     leaf_node_clone.rollback(&guard);
                                 }
                                 _ => (),
-                            };
+                            }
                         }
                         {
                             barrier_clone.wait().await;
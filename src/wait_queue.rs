@@ -3,15 +3,28 @@ use crate::maybe_std::yield_now;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::atomic::AtomicUsize;
-use std::sync::atomic::Ordering::{AcqRel, Relaxed};
+use std::sync::atomic::Ordering::{AcqRel, Acquire, Relaxed};
 use std::sync::{Condvar, Mutex};
 use std::task::{Context, Poll, Waker};
 use std::thread;
 
 /// `ASYNC` is a flag indicating that the referenced instance corresponds to an asynchronous
 /// operation.
+///
+/// Wait queue entries are aligned to 8 bytes, therefore the lower three bits of an entry address
+/// are available for flags.
 const ASYNC: usize = 1_usize;
 
+/// `SIGNALING` is a flag indicating that a thread is removing an entry from the [`WaitQueue`].
+///
+/// Entries are only released by their owners after being signaled, therefore the entries can be
+/// read by the thread while the flag is set.
+const SIGNALING: usize = 2_usize;
+
+/// `PENDING` is a flag indicating that all the entries have to be woken up once the thread
+/// removing an entry is done.
+const PENDING: usize = 4_usize;
+
 /// [`WaitQueue`] implements an unfair wait queue.
 ///
 /// The sole purpose of the data structure is to avoid busy-waiting. [`WaitQueue`] should always
@@ -33,17 +46,22 @@ impl WaitQueue {
         }
 
         let mut current = self.wait_queue.load(Relaxed);
-        let mut entry = SyncWait::new(current);
+        let mut entry = SyncWait::new(current & !(SIGNALING | PENDING));
         let mut entry_mut = Pin::new(&mut entry);
 
+        // The entry is pushed with `AcqRel`, therefore `signal` always observes the entry fully
+        // initialized.
         while let Err(actual) = self.wait_queue.compare_exchange_weak(
             current,
-            entry_mut.as_mut().get_mut() as *mut SyncWait as usize,
+            (entry_mut.as_mut().get_mut() as *mut SyncWait as usize)
+                | (current & (SIGNALING | PENDING)),
             AcqRel,
             Relaxed,
         ) {
             current = actual;
-            entry_mut.next.store(current, Relaxed);
+            entry_mut
+                .next
+                .store(current & !(SIGNALING | PENDING), Relaxed);
         }
 
         // Execute the closure.
@@ -70,7 +88,9 @@ impl WaitQueue {
 
         let mut current = self.wait_queue.load(Relaxed);
         let wait_queue_ref: &WaitQueue = self;
-        async_wait.next.store(current, Relaxed);
+        async_wait
+            .next
+            .store(current & !(SIGNALING | PENDING), Relaxed);
         async_wait.mutex.replace(Mutex::new((
             Some(unsafe { std::mem::transmute::<&WaitQueue, &WaitQueue>(wait_queue_ref) }),
             None,
@@ -78,12 +98,14 @@ impl WaitQueue {
 
         while let Err(actual) = self.wait_queue.compare_exchange_weak(
             current,
-            (async_wait as *mut AsyncWait as usize) | ASYNC,
+            (async_wait as *mut AsyncWait as usize) | ASYNC | (current & (SIGNALING | PENDING)),
             AcqRel,
             Relaxed,
         ) {
             current = actual;
-            async_wait.next.store(current, Relaxed);
+            async_wait
+                .next
+                .store(current & !(SIGNALING | PENDING), Relaxed);
         }
 
         // Execute the closure.
@@ -101,16 +123,179 @@ impl WaitQueue {
         Err(())
     }
 
+    /// Returns the number of entries in the [`WaitQueue`] for diagnostic purposes.
+    ///
+    /// The result is a snapshot that may already be outdated when returned, since entries can be
+    /// pushed at any time.
+    ///
+    /// # Safety
+    ///
+    /// Entries are owned by their waiters, and an entry may be dropped as soon as it is woken up,
+    /// therefore the caller has to make sure that [`WaitQueue::signal`] is not called, e.g., by
+    /// holding the resource the waiters are waiting for, and that no waiter gives up waiting
+    /// during the call.
+    #[cfg(test)]
+    pub(crate) unsafe fn num_entries(&self) -> usize {
+        let mut num_entries = 0;
+        let mut current = self.wait_queue.load(Acquire) & !(SIGNALING | PENDING);
+        while (current & (!ASYNC)) != 0 {
+            current = if (current & ASYNC) == 0 {
+                (*(current as *const SyncWait)).next.load(Relaxed)
+            } else {
+                (*((current & (!ASYNC)) as *const AsyncWait))
+                    .next
+                    .load(Relaxed)
+            };
+            num_entries += 1;
+        }
+        num_entries
+    }
+
     /// Signals the threads in the wait queue.
+    ///
+    /// If another thread is removing an entry from the wait queue, the entries are woken up by the
+    /// other thread.
     #[inline]
     pub(crate) fn signal(&self) {
         if cfg!(miri) || cfg!(feature = "loom") {
             return;
         }
 
-        let mut current = self.wait_queue.swap(0, AcqRel);
+        // Entries are only removed as a whole, or by the thread that set `SIGNALING`, and pushing
+        // an entry never reads the content of the entry it links to, therefore reusing the address
+        // of a woken entry causes no ABA problem.
+        let mut current = self.wait_queue.load(Relaxed);
+        loop {
+            let next = if (current & SIGNALING) == SIGNALING {
+                current | PENDING
+            } else {
+                0
+            };
+            match self
+                .wait_queue
+                .compare_exchange_weak(current, next, AcqRel, Relaxed)
+            {
+                Ok(_) => break,
+                Err(actual) => current = actual,
+            }
+        }
+        if (current & SIGNALING) == 0 {
+            Self::wake_up_all(current);
+        }
+    }
+
+    /// Signals the oldest thread in the wait queue.
+    ///
+    /// If another thread is removing an entry from the wait queue, all the entries are woken up by
+    /// the other thread.
+    #[inline]
+    pub(crate) fn signal_one(&self) {
+        if cfg!(miri) || cfg!(feature = "loom") {
+            return;
+        }
+
+        let mut current = self.wait_queue.load(Relaxed);
+        loop {
+            let next = if current == 0 {
+                0
+            } else if (current & SIGNALING) == SIGNALING {
+                current | PENDING
+            } else {
+                current | SIGNALING
+            };
+            match self
+                .wait_queue
+                .compare_exchange_weak(current, next, AcqRel, Relaxed)
+            {
+                Ok(_) => {
+                    if current == 0 || (current & SIGNALING) == SIGNALING {
+                        return;
+                    }
+                    break;
+                }
+                Err(actual) => current = actual,
+            }
+        }
+
+        let oldest = self.unlink_oldest(current | SIGNALING);
+        unsafe {
+            Self::signal_entry(oldest);
+        }
+
+        current = self.wait_queue.load(Relaxed);
+        loop {
+            let next = if (current & PENDING) == PENDING {
+                0
+            } else {
+                current & !SIGNALING
+            };
+            match self
+                .wait_queue
+                .compare_exchange_weak(current, next, AcqRel, Relaxed)
+            {
+                Ok(_) => break,
+                Err(actual) => current = actual,
+            }
+        }
+        if (current & PENDING) == PENDING {
+            Self::wake_up_all(current & !(SIGNALING | PENDING));
+        }
+    }
 
+    /// Unlinks the oldest entry from the wait queue while `SIGNALING` is set.
+    fn unlink_oldest(&self, mut head: usize) -> usize {
+        loop {
+            let mut prev = 0;
+            let mut current = head & !(SIGNALING | PENDING);
+            loop {
+                let next = unsafe { Self::next_link(current).load(Relaxed) };
+                if next == 0 {
+                    break;
+                }
+                prev = current;
+                current = next;
+            }
+            if prev != 0 {
+                // Other threads only push entries in front of the newest one.
+                unsafe {
+                    Self::next_link(prev).store(0, Relaxed);
+                }
+                return current;
+            }
+            match self.wait_queue.compare_exchange_weak(
+                head,
+                head & (SIGNALING | PENDING),
+                AcqRel,
+                Acquire,
+            ) {
+                Ok(_) => return current,
+                Err(actual) => head = actual,
+            }
+        }
+    }
+
+    /// Returns a reference to the link of the entry.
+    unsafe fn next_link<'e>(entry: usize) -> &'e AtomicUsize {
+        if (entry & ASYNC) == 0 {
+            &(*(entry as *const SyncWait)).next
+        } else {
+            &(*((entry & (!ASYNC)) as *const AsyncWait)).next
+        }
+    }
+
+    /// Signals the entry.
+    unsafe fn signal_entry(entry: usize) {
+        if (entry & ASYNC) == 0 {
+            (*(entry as *const SyncWait)).signal();
+        } else {
+            (*((entry & (!ASYNC)) as *const AsyncWait)).signal();
+        }
+    }
+
+    /// Wakes up all the entries linked to `head`.
+    fn wake_up_all(head: usize) {
         // Flip the queue to prioritize oldest entries.
+        let mut current = head;
         let mut prev = 0;
         while (current & (!ASYNC)) != 0 {
             current = if (current & ASYNC) == 0 {
@@ -136,7 +321,11 @@ impl WaitQueue {
             };
         }
 
-        // Wake up all the tasks.
+        // Wake up all the tasks; woken up threads compete for the resource again without any
+        // ordering guarantee. Asynchronous entries are never partially woken up, since a detached
+        // entry that is not pushed back yet cannot be pulled out by its owner when the
+        // `AsyncWait` is dropped, therefore the owner of the resource should only signal the
+        // queue when waiting threads can make progress.
         current = prev;
         while (current & (!ASYNC)) != 0 {
             current = if (current & ASYNC) == 0 {
@@ -186,6 +375,7 @@ impl DeriveAsyncWait for () {
 /// therefore it can be moved, however the [`DeriveAsyncWait`] trait forces [`AsyncWait`] to be
 /// pinned.
 #[derive(Debug, Default)]
+#[repr(align(8))]
 pub(crate) struct AsyncWait {
     next: AtomicUsize,
     mutex: Option<Mutex<(Option<&'static WaitQueue>, Option<Waker>)>>,
@@ -283,6 +473,7 @@ impl Future for AsyncWait {
 /// [`SyncWait`] is inserted into [`WaitQueue`] for the caller to synchronously wait until
 /// signaled.
 #[derive(Debug)]
+#[repr(align(8))]
 struct SyncWait {
     next: AtomicUsize,
     condvar: Condvar,
@@ -301,6 +492,9 @@ impl SyncWait {
     }
 
     /// Waits for a signal.
+    ///
+    /// The thread is parked on the condition variable without spinning; wakeups cannot be lost
+    /// since the flag is only read and updated while the mutex is held.
     fn wait(&self) {
         #[allow(clippy::mutex_atomic)]
         let mut completed = unsafe { self.mutex.lock().unwrap_unchecked() };
@@ -311,6 +505,8 @@ impl SyncWait {
 
     /// Sends a signal.
     fn signal(&self) {
+        // The woken up thread acquires the same mutex, therefore any memory writes made before
+        // `WaitQueue::signal` are visible to the thread.
         #[allow(clippy::mutex_atomic)]
         let mut completed = unsafe { self.mutex.lock().unwrap_unchecked() };
         *completed = true;
@@ -322,7 +518,8 @@ impl SyncWait {
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::sync::atomic::Ordering::Release;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::atomic::Ordering::{Release, SeqCst};
     use std::sync::Arc;
     use std::sync::Barrier;
     use std::thread::yield_now;
@@ -364,9 +561,9 @@ mod test {
         data.fetch_add(1, Release);
         wait_queue.signal();
 
-        task_handles
-            .into_iter()
-            .for_each(|t| assert!(t.join().is_ok()));
+        for t in task_handles {
+            assert!(t.join().is_ok());
+        }
     }
 
     #[cfg_attr(miri, ignore)]
@@ -455,4 +652,420 @@ mod test {
         }
         drop(wait_queue);
     }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn wait_queue_sync_parked() {
+        let wait_queue = Arc::new(WaitQueue::default());
+        let num_polls = Arc::new(AtomicUsize::new(0));
+        let woken = Arc::new(AtomicBool::new(false));
+        let wait_queue_clone = wait_queue.clone();
+        let num_polls_clone = num_polls.clone();
+        let woken_clone = woken.clone();
+        let thread_handle = std::thread::spawn(move || {
+            let result = wait_queue_clone.wait_sync(|| {
+                num_polls_clone.fetch_add(1, Relaxed);
+                Err::<(), ()>(())
+            });
+            woken_clone.store(true, Release);
+            result
+        });
+
+        while num_polls.load(Relaxed) == 0 {
+            yield_now();
+        }
+
+        // The thread stays in the wait queue without retrying until it is signaled.
+        std::thread::sleep(std::time::Duration::from_millis(64));
+        assert_eq!(num_polls.load(Relaxed), 1);
+        assert!(!woken.load(Acquire));
+        assert_ne!(wait_queue.wait_queue.load(Relaxed), 0);
+
+        wait_queue.signal();
+        assert!(thread_handle.join().unwrap().is_err());
+        assert!(woken.load(Acquire));
+        assert_eq!(num_polls.load(Relaxed), 1);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn wait_queue_sync_publish() {
+        for i in 1..=256 {
+            let wait_queue = Arc::new(WaitQueue::default());
+            let data = Arc::new([AtomicUsize::new(0), AtomicUsize::new(0)]);
+            let wait_queue_clone = wait_queue.clone();
+            let data_clone = data.clone();
+            let thread_handle = std::thread::spawn(move || {
+                if wait_queue_clone
+                    .wait_sync(|| {
+                        if data_clone[1].load(Relaxed) == i {
+                            Ok(())
+                        } else {
+                            Err(())
+                        }
+                    })
+                    .is_err()
+                {
+                    // The data written before `signal` must be visible after being woken up.
+                    assert_eq!(data_clone[0].load(Relaxed), i);
+                    assert_eq!(data_clone[1].load(Relaxed), i);
+                }
+            });
+
+            while wait_queue.wait_queue.load(Relaxed) == 0 {
+                yield_now();
+            }
+            data[0].store(i, Relaxed);
+            data[1].store(i, Relaxed);
+            wait_queue.signal();
+            assert!(thread_handle.join().is_ok());
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn wait_queue_churn() {
+        const LOCKED: usize = 1;
+        const WAITING: usize = 2;
+
+        fn try_lock(state: &AtomicUsize) -> Result<(), ()> {
+            let current = state.load(Relaxed) & WAITING;
+            state
+                .compare_exchange(current, current | LOCKED, Acquire, Relaxed)
+                .map(|_| ())
+                .map_err(|_| ())
+        }
+
+        let num_threads = 8;
+        let num_iters = 4096;
+        let state = Arc::new(AtomicUsize::new(0));
+        let data = Arc::new(AtomicUsize::new(0));
+        let wait_queue = Arc::new(WaitQueue::default());
+        let barrier = Arc::new(Barrier::new(num_threads));
+        let mut thread_handles = Vec::with_capacity(num_threads);
+        for _ in 0..num_threads {
+            let state = state.clone();
+            let data = data.clone();
+            let wait_queue = wait_queue.clone();
+            let barrier = barrier.clone();
+            thread_handles.push(std::thread::spawn(move || {
+                barrier.wait();
+                for _ in 0..num_iters {
+                    // Each entry is placed on the stack, therefore addresses are reused frequently.
+                    while try_lock(&state).is_err()
+                        && wait_queue
+                            .wait_sync(|| {
+                                state.fetch_or(WAITING, Release);
+                                try_lock(&state)
+                            })
+                            .is_err()
+                    {}
+                    data.store(data.load(Relaxed) + 1, Relaxed);
+                    for _ in 0..64 {
+                        std::hint::spin_loop();
+                    }
+                    if state.swap(0, Release) & WAITING == WAITING {
+                        wait_queue.signal();
+                    }
+                }
+            }));
+        }
+        for thread_handle in thread_handles {
+            assert!(thread_handle.join().is_ok());
+        }
+        assert_eq!(data.load(Relaxed), num_threads * num_iters);
+        assert_eq!(wait_queue.wait_queue.load(Relaxed), 0);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn wait_queue_num_entries() {
+        let num_threads = 4;
+        let num_tasks = 4;
+        let wait_queue = Arc::new(WaitQueue::default());
+        assert_eq!(unsafe { wait_queue.num_entries() }, 0);
+
+        let mut async_waits: Vec<Pin<Box<AsyncWait>>> = (0..num_tasks)
+            .map(|_| Box::pin(AsyncWait::default()))
+            .collect();
+        for async_wait in &mut async_waits {
+            let async_wait_mut = unsafe { async_wait.as_mut().get_unchecked_mut() };
+            assert!(wait_queue
+                .push_async_entry(async_wait_mut, || Err::<(), ()>(()))
+                .is_err());
+        }
+        assert_eq!(unsafe { wait_queue.num_entries() }, num_tasks);
+
+        // The waiting threads are held until `signal` is called.
+        let mut thread_handles = Vec::with_capacity(num_threads);
+        for _ in 0..num_threads {
+            let wait_queue = wait_queue.clone();
+            thread_handles.push(std::thread::spawn(move || {
+                assert!(wait_queue.wait_sync(|| Err::<(), ()>(())).is_err());
+            }));
+        }
+        loop {
+            let num_entries = unsafe { wait_queue.num_entries() };
+            assert!((num_tasks..=num_tasks + num_threads).contains(&num_entries));
+            if num_entries == num_tasks + num_threads {
+                break;
+            }
+            yield_now();
+        }
+
+        wait_queue.signal();
+        assert_eq!(unsafe { wait_queue.num_entries() }, 0);
+        for thread_handle in thread_handles {
+            assert!(thread_handle.join().is_ok());
+        }
+        for async_wait in &mut async_waits {
+            let mut context = Context::from_waker(futures::task::noop_waker_ref());
+            assert!(async_wait.as_mut().poll(&mut context).is_ready());
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn wait_queue_signal_one() {
+        let num_threads = 4;
+        let wait_queue = Arc::new(WaitQueue::default());
+        let num_woken = Arc::new(AtomicUsize::new(0));
+        let mut thread_handles = Vec::with_capacity(num_threads);
+        for _ in 0..num_threads {
+            let wait_queue = wait_queue.clone();
+            let num_woken = num_woken.clone();
+            thread_handles.push(std::thread::spawn(move || {
+                assert!(wait_queue.wait_sync(|| Err::<(), ()>(())).is_err());
+                num_woken.fetch_add(1, Release);
+            }));
+        }
+        while unsafe { wait_queue.num_entries() } != num_threads {
+            yield_now();
+        }
+
+        // Each signal wakes up exactly one thread, and the others stay in the queue.
+        for i in 1..=num_threads {
+            wait_queue.signal_one();
+            assert_eq!(unsafe { wait_queue.num_entries() }, num_threads - i);
+            while num_woken.load(Acquire) != i {
+                yield_now();
+            }
+            std::thread::sleep(std::time::Duration::from_millis(4));
+            assert_eq!(num_woken.load(Acquire), i);
+        }
+        assert_eq!(wait_queue.wait_queue.load(Relaxed), 0);
+        for thread_handle in thread_handles {
+            assert!(thread_handle.join().is_ok());
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn wait_queue_wake_order() {
+        struct Recorder(usize, Arc<Mutex<Vec<usize>>>);
+        impl std::task::Wake for Recorder {
+            fn wake(self: Arc<Self>) {
+                self.1.lock().unwrap().push(self.0);
+            }
+        }
+
+        let wait_queue = WaitQueue::default();
+        let woken = Arc::new(Mutex::new(Vec::new()));
+        let num_entries = 8;
+        let mut async_waits: Vec<Pin<Box<AsyncWait>>> = (0..num_entries)
+            .map(|_| Box::pin(AsyncWait::default()))
+            .collect();
+        for (id, async_wait) in async_waits.iter_mut().enumerate() {
+            let async_wait_mut = unsafe { async_wait.as_mut().get_unchecked_mut() };
+            assert!(wait_queue
+                .push_async_entry(async_wait_mut, || Err::<(), ()>(()))
+                .is_err());
+            let waker = Arc::new(Recorder(id, woken.clone())).into();
+            let mut context = Context::from_waker(&waker);
+            assert!(async_wait.as_mut().poll(&mut context).is_pending());
+        }
+
+        // The oldest entry is woken up first.
+        wait_queue.signal();
+        assert_eq!(*woken.lock().unwrap(), (0..num_entries).collect::<Vec<_>>());
+        for async_wait in &mut async_waits {
+            let waker = Arc::new(Recorder(usize::MAX, woken.clone())).into();
+            let mut context = Context::from_waker(&waker);
+            assert!(async_wait.as_mut().poll(&mut context).is_ready());
+        }
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn wait_queue_signal_one_order() {
+        type LateEntry = Arc<Mutex<Pin<Box<AsyncWait>>>>;
+        struct Recorder {
+            id: usize,
+            woken: Arc<Mutex<Vec<usize>>>,
+            late_entry: Option<(Arc<WaitQueue>, LateEntry)>,
+        }
+        impl std::task::Wake for Recorder {
+            fn wake(self: Arc<Self>) {
+                self.woken.lock().unwrap().push(self.id);
+                if let Some((wait_queue, late_entry)) = self.late_entry.as_ref() {
+                    // A new entry arrives while `signal_one` is removing the oldest entry.
+                    let mut late_entry = late_entry.lock().unwrap();
+                    let async_wait_mut = unsafe { late_entry.as_mut().get_unchecked_mut() };
+                    assert!(wait_queue
+                        .push_async_entry(async_wait_mut, || Err::<(), ()>(()))
+                        .is_err());
+                }
+            }
+        }
+
+        let wait_queue = Arc::new(WaitQueue::default());
+        let woken = Arc::new(Mutex::new(Vec::new()));
+        let late_entry = Arc::new(Mutex::new(Box::pin(AsyncWait::default())));
+        let num_entries = 8;
+        let mut async_waits: Vec<Pin<Box<AsyncWait>>> = (0..num_entries)
+            .map(|_| Box::pin(AsyncWait::default()))
+            .collect();
+        for (id, async_wait) in async_waits.iter_mut().enumerate() {
+            let async_wait_mut = unsafe { async_wait.as_mut().get_unchecked_mut() };
+            assert!(wait_queue
+                .push_async_entry(async_wait_mut, || Err::<(), ()>(()))
+                .is_err());
+            let recorder = Recorder {
+                id,
+                woken: woken.clone(),
+                late_entry: (id == 0).then(|| (wait_queue.clone(), late_entry.clone())),
+            };
+            let waker = Arc::new(recorder).into();
+            let mut context = Context::from_waker(&waker);
+            assert!(async_wait.as_mut().poll(&mut context).is_pending());
+        }
+
+        wait_queue.signal_one();
+        assert_eq!(*woken.lock().unwrap(), vec![0]);
+        assert_eq!(unsafe { wait_queue.num_entries() }, num_entries);
+        let recorder = Recorder {
+            id: num_entries,
+            woken: woken.clone(),
+            late_entry: None,
+        };
+        let waker = Arc::new(recorder).into();
+        let mut context = Context::from_waker(&waker);
+        assert!(late_entry
+            .lock()
+            .unwrap()
+            .as_mut()
+            .poll(&mut context)
+            .is_pending());
+
+        // Each signal wakes up the oldest entry, and the late entry is woken up last.
+        for i in 1..=num_entries {
+            wait_queue.signal_one();
+            assert_eq!(*woken.lock().unwrap(), (0..=i).collect::<Vec<_>>());
+        }
+        assert_eq!(wait_queue.wait_queue.load(Relaxed), 0);
+        let mut context = Context::from_waker(futures::task::noop_waker_ref());
+        for async_wait in &mut async_waits {
+            assert!(async_wait.as_mut().poll(&mut context).is_ready());
+        }
+        assert!(late_entry
+            .lock()
+            .unwrap()
+            .as_mut()
+            .poll(&mut context)
+            .is_ready());
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn wait_queue_signal_one_churn() {
+        fn try_take(tokens: &AtomicUsize) -> Result<(), ()> {
+            let mut current = tokens.load(Relaxed);
+            while current != 0 {
+                match tokens.compare_exchange_weak(current, current - 1, SeqCst, Relaxed) {
+                    Ok(_) => return Ok(()),
+                    Err(actual) => current = actual,
+                }
+            }
+            Err(())
+        }
+
+        // Waiting threads keep arriving while tokens are handed out one by one, therefore a lost
+        // or misplaced entry makes a thread wait forever.
+        let num_threads = 8;
+        let num_iters = 4096;
+        let tokens = Arc::new(AtomicUsize::new(0));
+        let wait_queue = Arc::new(WaitQueue::default());
+        let (sender, receiver) = std::sync::mpsc::channel();
+        for id in 0..num_threads {
+            let tokens = tokens.clone();
+            let wait_queue = wait_queue.clone();
+            let sender = sender.clone();
+            std::thread::spawn(move || {
+                for _ in 0..num_iters {
+                    if id % 2 == 0 {
+                        tokens.fetch_add(1, SeqCst);
+                        wait_queue.signal_one();
+                    } else {
+                        while try_take(&tokens).is_err()
+                            && wait_queue
+                                .wait_sync(|| {
+                                    std::sync::atomic::fence(SeqCst);
+                                    try_take(&tokens)
+                                })
+                                .is_err()
+                        {}
+                    }
+                }
+                sender.send(id).unwrap();
+            });
+        }
+        for _ in 0..num_threads {
+            assert!(receiver
+                .recv_timeout(std::time::Duration::from_secs(60))
+                .is_ok());
+        }
+        assert_eq!(tokens.load(Relaxed), 0);
+        assert_eq!(wait_queue.wait_queue.load(Relaxed), 0);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn wait_queue_handoff() {
+        // Two threads take turns, and each signal is the only way for the other thread to proceed,
+        // therefore a lost wakeup makes both threads wait forever.
+        let num_iters = 16384;
+        let turn = Arc::new(AtomicUsize::new(0));
+        let wait_queue = Arc::new(WaitQueue::default());
+        let (sender, receiver) = std::sync::mpsc::channel();
+        for id in 0..2 {
+            let turn = turn.clone();
+            let wait_queue = wait_queue.clone();
+            let sender = sender.clone();
+            std::thread::spawn(move || {
+                for i in 0..num_iters {
+                    let expected = i * 2 + id;
+                    while turn.load(Acquire) != expected
+                        && wait_queue
+                            .wait_sync(|| {
+                                if turn.load(Acquire) == expected {
+                                    Ok(())
+                                } else {
+                                    Err(())
+                                }
+                            })
+                            .is_err()
+                    {}
+                    turn.store(expected + 1, Release);
+                    wait_queue.signal();
+                }
+                sender.send(id).unwrap();
+            });
+        }
+        for _ in 0..2 {
+            assert!(receiver
+                .recv_timeout(std::time::Duration::from_secs(60))
+                .is_ok());
+        }
+        assert_eq!(turn.load(Relaxed), num_iters * 2);
+    }
 }